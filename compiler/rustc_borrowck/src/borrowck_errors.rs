@@ -367,8 +367,12 @@ impl<'cx, 'tcx> crate::MirBorrowckCtxt<'cx, 'tcx> {
             immutable_place,
             immutable_section,
         );
-        err.span_label(mutate_span, format!("cannot {}", action));
+        err.span_label(mutate_span, format!("cannot {} {}", action, immutable_place));
         err.span_label(immutable_span, format!("value is immutable in {}", immutable_section));
+        if immutable_section == "match guard" {
+            err.note("the value being matched must not change while a match guard is evaluated");
+            err.help("consider moving the mutation into the arm body");
+        }
         err
     }
 
@@ -313,13 +313,16 @@ fn report_cannot_move_from_borrowed_content(
         if let PlaceRef { local, projection: [] } = deref_base {
             let decl = &self.body.local_decls[local];
             if decl.is_ref_for_guard() {
-                let mut err = self.cannot_move_out_of(
-                    span,
-                    &format!("`{}` in pattern guard", self.local_names[local].unwrap()),
+                let name = self.local_names[local].unwrap();
+                let mut err =
+                    self.cannot_move_out_of(span, &format!("`{}` in pattern guard", name));
+                err.span_label(
+                    decl.source_info.span,
+                    format!("`{}` is bound by the pattern here", name),
                 );
                 err.note(
-                    "variables bound in patterns cannot be moved from \
-                     until after the end of the pattern guard",
+                    "the guard runs before the arm is committed to, so variables bound in \
+                     patterns cannot be moved from until after the end of the pattern guard",
                 );
                 return err;
             } else if decl.is_ref_to_static() {
@@ -198,6 +198,39 @@ fn switch(
         }
     }
 
+    fn switch_with_cold_targets(
+        &mut self,
+        v: &'ll Value,
+        (else_llbb, else_is_cold): (&'ll BasicBlock, bool),
+        cases: impl ExactSizeIterator<Item = (u128, &'ll BasicBlock, bool)>,
+    ) {
+        let switch =
+            unsafe { llvm::LLVMBuildSwitch(self.llbuilder, v, else_llbb, cases.len() as c_uint) };
+        let mut is_cold = vec![else_is_cold];
+        for (on_val, dest, dest_is_cold) in cases {
+            let on_val = self.const_uint_big(self.val_ty(v), on_val);
+            unsafe { llvm::LLVMAddCase(switch, on_val, dest) }
+            is_cold.push(dest_is_cold);
+        }
+        if is_cold.iter().all(|&cold| cold) || !is_cold.iter().any(|&cold| cold) {
+            return;
+        }
+
+        // The same weights that LLVM gives the two sides of an `llvm.expect`.
+        let name = "branch_weights";
+        let mut weights = vec![unsafe {
+            llvm::LLVMMDStringInContext(self.cx.llcx, name.as_ptr().cast(), name.len() as c_uint)
+        }];
+        weights.extend(is_cold.iter().map(|&cold| self.cx.const_u32(if cold { 1 } else { 2000 })));
+        unsafe {
+            llvm::LLVMSetMetadata(
+                switch,
+                llvm::MD_prof as c_uint,
+                llvm::LLVMMDNodeInContext(self.cx.llcx, weights.as_ptr(), weights.len() as c_uint),
+            );
+        }
+    }
+
     fn invoke(
         &mut self,
         llty: &'ll Type,
@@ -213,28 +213,62 @@ fn codegen_switchint_terminator(
             let (test_value, target) = target_iter.next().unwrap();
             let lltrue = helper.llblock(self, target);
             let llfalse = helper.llblock(self, targets.otherwise());
+            // If exactly one side can only end in a diverging call (e.g. a `panic!` match arm),
+            // pass the condition through llvm.expect so the other side is laid out as hot.
+            let otherwise_is_cold = self.is_cold_block(targets.otherwise());
+            let expected = match (self.is_cold_block(target), otherwise_is_cold) {
+                (false, true) => Some(true),
+                (true, false) => Some(false),
+                _ => None,
+            };
             if switch_ty == bx.tcx().types.bool {
                 // Don't generate trivial icmps when switching on bool
+                let mut cond = discr.immediate();
+                if let Some(expected) = expected {
+                    cond = bx.expect(cond, expected == (test_value == 1));
+                }
                 match test_value {
-                    0 => bx.cond_br(discr.immediate(), llfalse, lltrue),
-                    1 => bx.cond_br(discr.immediate(), lltrue, llfalse),
+                    0 => bx.cond_br(cond, llfalse, lltrue),
+                    1 => bx.cond_br(cond, lltrue, llfalse),
                     _ => bug!(),
                 }
             } else {
                 let switch_llty = bx.immediate_backend_type(bx.layout_of(switch_ty));
                 let llval = bx.const_uint_big(switch_llty, test_value);
-                let cmp = bx.icmp(IntPredicate::IntEQ, discr.immediate(), llval);
+                let mut cmp = bx.icmp(IntPredicate::IntEQ, discr.immediate(), llval);
+                if let Some(expected) = expected {
+                    cmp = bx.expect(cmp, expected);
+                }
                 bx.cond_br(cmp, lltrue, llfalse);
             }
         } else {
-            bx.switch(
+            // Give the targets that can only end in a diverging call a low branch weight.
+            let otherwise = targets.otherwise();
+            bx.switch_with_cold_targets(
                 discr.immediate(),
-                helper.llblock(self, targets.otherwise()),
-                target_iter.map(|(value, target)| (value, helper.llblock(self, target))),
+                (helper.llblock(self, otherwise), self.is_cold_block(otherwise)),
+                target_iter.map(|(value, target)| {
+                    (value, helper.llblock(self, target), self.is_cold_block(target))
+                }),
             );
         }
     }
 
+    /// Whether every path out of `bb` ends in a call that never returns or is unreachable, looking
+    /// through a few `Goto`s. Such blocks are the arms that panic or abort, and the otherwise
+    /// branches of exhaustive switches.
+    fn is_cold_block(&self, mut bb: mir::BasicBlock) -> bool {
+        for _ in 0..8 {
+            match self.mir[bb].terminator().kind {
+                mir::TerminatorKind::Goto { target } => bb = target,
+                mir::TerminatorKind::Call { destination: None, .. }
+                | mir::TerminatorKind::Unreachable => return true,
+                _ => return false,
+            }
+        }
+        false
+    }
+
     fn codegen_return_terminator(&mut self, mut bx: Bx) {
         // Call `va_end` if this is the definition of a C-variadic function.
         if self.fn_abi.c_variadic {
@@ -68,6 +68,16 @@ fn switch(
         else_llbb: Self::BasicBlock,
         cases: impl ExactSizeIterator<Item = (u128, Self::BasicBlock)>,
     );
+    /// Like `switch`, but `else_llbb` and every case say whether they are cold, i.e. unlikely to
+    /// be taken. Backends that can't express branch weights ignore that.
+    fn switch_with_cold_targets(
+        &mut self,
+        v: Self::Value,
+        (else_llbb, _): (Self::BasicBlock, bool),
+        cases: impl ExactSizeIterator<Item = (u128, Self::BasicBlock, bool)>,
+    ) {
+        self.switch(v, else_llbb, cases.map(|(value, llbb, _)| (value, llbb)))
+    }
     fn invoke(
         &mut self,
         llty: Self::Type,
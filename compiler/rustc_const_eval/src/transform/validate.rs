@@ -1,14 +1,16 @@
 //! Validates the MIR to ensure that invariants are upheld.
 
 use super::MirPass;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::graph::dominators::Dominators;
 use rustc_index::bit_set::BitSet;
 use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::mir::interpret::Scalar;
 use rustc_middle::mir::traversal;
 use rustc_middle::mir::visit::{PlaceContext, Visitor};
 use rustc_middle::mir::{
-    AggregateKind, BasicBlock, Body, BorrowKind, Local, Location, MirPhase, Operand, PlaceElem,
-    PlaceRef, ProjectionElem, Rvalue, SourceScope, Statement, StatementKind, Terminator,
+    AggregateKind, BasicBlock, Body, BorrowKind, FakeReadCause, Local, Location, MirPhase, Operand,
+    PlaceElem, PlaceRef, ProjectionElem, Rvalue, SourceScope, Statement, StatementKind, Terminator,
     TerminatorKind,
 };
 use rustc_middle::ty::fold::BottomUpFolder;
@@ -47,6 +49,22 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
             .iterate_to_fixpoint()
             .into_results_cursor(body);
 
+        // The fake borrows that match lowering takes for guards, and where they are taken.
+        let mut fake_borrows: FxHashMap<Local, Vec<Location>> = FxHashMap::default();
+        for (block, data) in body.basic_blocks().iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                if let StatementKind::Assign(box (dest, Rvalue::Ref(_, BorrowKind::Shallow, _))) =
+                    &statement.kind
+                {
+                    if let Some(local) = dest.as_local() {
+                        let location = Location { block, statement_index };
+                        fake_borrows.entry(local).or_default().push(location);
+                    }
+                }
+            }
+        }
+        let dominators = if fake_borrows.is_empty() { None } else { Some(body.dominators()) };
+
         TypeChecker {
             when: &self.when,
             body,
@@ -56,6 +74,8 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
             reachable_blocks: traversal::reachable_as_bitset(body),
             storage_liveness,
             place_cache: Vec::new(),
+            fake_borrows,
+            dominators,
         }
         .visit_body(body);
     }
@@ -110,6 +130,9 @@ struct TypeChecker<'a, 'tcx> {
     reachable_blocks: BitSet<BasicBlock>,
     storage_liveness: ResultsCursor<'a, 'tcx, MaybeStorageLive>,
     place_cache: Vec<PlaceRef<'tcx>>,
+    fake_borrows: FxHashMap<Local, Vec<Location>>,
+    /// Only computed if there are fake borrows to check.
+    dominators: Option<Dominators<BasicBlock>>,
 }
 
 impl<'a, 'tcx> TypeChecker<'a, 'tcx> {
@@ -303,13 +326,36 @@ fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
                     );
                 }
             }
-            StatementKind::FakeRead(..) => {
+            StatementKind::FakeRead(box (cause, place)) => {
                 if self.mir_phase > MirPhase::DropLowering {
                     self.fail(
                         location,
                         "`FakeRead` should have been removed after drop lowering phase",
                     );
                 }
+                if *cause == FakeReadCause::ForMatchGuard
+                    && self.reachable_blocks.contains(location.block)
+                {
+                    // The fake borrows that keep a guard from changing the matched places must be
+                    // taken on every path into the guard.
+                    let is_borrowed = match (place.as_local(), &self.dominators) {
+                        (Some(local), Some(dominators)) => {
+                            self.fake_borrows.get(&local).map_or(false, |borrows| {
+                                borrows.iter().any(|borrow| borrow.dominates(location, dominators))
+                            })
+                        }
+                        _ => false,
+                    };
+                    if !is_borrowed {
+                        self.fail(
+                            location,
+                            format!(
+                                "`FakeRead(ForMatchGuard, {:?})` is not dominated by a fake borrow",
+                                place
+                            ),
+                        );
+                    }
+                }
             }
             StatementKind::CopyNonOverlapping(box rustc_middle::mir::CopyNonOverlapping {
                 ref src,
@@ -410,6 +410,10 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.opts.debugging_opts.match_check_stats {
+                sess.code_stats.print_match_check_stats();
+            }
+
             let linker = queries.linker()?;
             Ok(Some(linker))
         })?;
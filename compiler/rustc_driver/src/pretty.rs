@@ -500,6 +500,17 @@ fn print_with_analysis(
             out
         }
 
+        MatchDecisionTree => {
+            let mut out = String::new();
+            for did in tcx.body_owners() {
+                let tree = tcx.match_decision_tree(ty::WithOptConstParam::unknown(did));
+                if !tree.is_empty() {
+                    let _ = writeln!(out, "{}:\n{}", tcx.def_path_str(did.to_def_id()), tree);
+                }
+            }
+            out
+        }
+
         _ => unreachable!(),
     };
 
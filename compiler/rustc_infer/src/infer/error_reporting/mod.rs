@@ -683,10 +683,17 @@ fn note_error_origin(
                             any_multiline_arm |= source_map.is_multiline(*sp);
                             err.span_label(*sp, format!("this is found to be of type `{}`", t));
                         }
-                    } else if let Some(sp) = prior_arms.last() {
-                        any_multiline_arm |= source_map.is_multiline(*sp);
+                    } else if let [first, .., last] = &prior_arms[..] {
+                        // In long matches the arm that determined the type is often far from the
+                        // mismatching one, so point at it as well.
+                        any_multiline_arm |= source_map.is_multiline(*first);
+                        any_multiline_arm |= source_map.is_multiline(*last);
                         err.span_label(
-                            *sp,
+                            *first,
+                            format!("the first arm is found to be of type `{}`", t),
+                        );
+                        err.span_label(
+                            *last,
                             format!("this and all prior arms are found to be of type `{}`", t),
                         );
                     }
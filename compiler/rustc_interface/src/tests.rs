@@ -640,6 +640,7 @@ macro_rules! untracked {
     untracked!(dep_tasks, true);
     untracked!(dont_buffer_diagnostics, true);
     untracked!(dump_dep_graph, true);
+    untracked!(dump_match_check, Some(String::from("abc")));
     untracked!(dump_mir, Some(String::from("abc")));
     untracked!(dump_mir_dataflow, true);
     untracked!(dump_mir_dir, String::from("abc"));
@@ -659,6 +660,7 @@ macro_rules! untracked {
     untracked!(llvm_time_trace, true);
     untracked!(ls, true);
     untracked!(macro_backtrace, true);
+    untracked!(match_check_stats, true);
     untracked!(meta_stats, true);
     untracked!(nll_facts, true);
     untracked!(no_analysis, true);
@@ -693,6 +695,7 @@ macro_rules! untracked {
     untracked!(ui_testing, true);
     untracked!(unpretty, Some("expanded".to_string()));
     untracked!(unstable_options, true);
+    untracked!(validate_match_lowering, true);
     untracked!(validate_mir, true);
     untracked!(verbose, true);
 
@@ -732,6 +735,7 @@ macro_rules! tracked {
     tracked!(instrument_mcount, true);
     tracked!(link_only, true);
     tracked!(llvm_plugins, vec![String::from("plugin_name")]);
+    tracked!(match_check_budget, Some(100));
     tracked!(merge_functions, Some(MergeFunctions::Disabled));
     tracked!(mir_emit_retag, true);
     tracked!(mir_opt_level, Some(4));
@@ -599,6 +599,39 @@
     "detects unreachable patterns"
 }
 
+declare_lint! {
+    /// The `repeated_guarded_arms` lint detects guarded `match` arms that
+    /// repeat both the pattern and the guard of an earlier arm.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// let x = Some(5);
+    /// match x {
+    ///     Some(n) if n > 3 => (),
+    ///     Some(n) if n > 3 => (),
+    ///     _ => (),
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Reachability checking ignores guarded arms, since a guard can fail.
+    /// But when a guard has no side effects, the second arm is only tried
+    /// after the same guard already failed for the same values, so it can
+    /// never be taken. This usually means the arm was copied and one of its
+    /// copies was not edited as intended.
+    ///
+    /// Only guards made of literals, paths, field accesses and built-in
+    /// operators are compared, so guards that call functions or overloaded
+    /// operators are never reported.
+    pub REPEATED_GUARDED_ARMS,
+    Warn,
+    "detects guarded match arms that repeat the pattern and guard of an earlier arm"
+}
+
 declare_lint! {
     /// The `overlapping_range_endpoints` lint detects `match` arms that have [range patterns] that
     /// overlap on their endpoints.
@@ -672,6 +705,46 @@
     "detects pattern bindings with the same name as one of the matched variants"
 }
 
+declare_lint! {
+    /// The `bindings_with_similar_variant_name` lint detects capitalized
+    /// pattern bindings whose name is close to one of the unit variants of
+    /// the matched enum.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![allow(non_snake_case, unused_variables)]
+    ///
+    /// pub enum Color {
+    ///     Red,
+    ///     Green,
+    ///     Blue,
+    /// }
+    ///
+    /// pub fn is_green(c: Color) -> bool {
+    ///     match c {
+    ///         Gren => true,
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// An identifier in a pattern that doesn't resolve to a unit variant or
+    /// constant introduces a new binding, which matches any value. A
+    /// capitalized binding that is spelled almost like a variant is most
+    /// likely a misspelling of that variant. In the example above, the arm
+    /// matches every color, not just `Color::Green`.
+    ///
+    /// Fix the spelling and qualify the path, such as `Color::Green`. If the
+    /// binding is intended, give it a lowercase name.
+    pub BINDINGS_WITH_SIMILAR_VARIANT_NAME,
+    Warn,
+    "detects pattern bindings named similarly to one of the matched unit variants"
+}
+
 declare_lint! {
     /// The `unused_macros` lint detects macros that were not used.
     ///
@@ -2936,8 +3009,10 @@
         DEAD_CODE,
         UNREACHABLE_CODE,
         UNREACHABLE_PATTERNS,
+        REPEATED_GUARDED_ARMS,
         OVERLAPPING_RANGE_ENDPOINTS,
         BINDINGS_WITH_VARIANT_NAME,
+        BINDINGS_WITH_SIMILAR_VARIANT_NAME,
         UNUSED_MACROS,
         WARNINGS,
         UNUSED_FEATURES,
@@ -259,6 +259,15 @@
         desc { |tcx| "constructing THIR tree for `{}`", tcx.def_path_str(key.did.to_def_id()) }
     }
 
+    /// Render the decision trees that MIR building lowers the matches of a body to.
+    query match_decision_tree(key: ty::WithOptConstParam<LocalDefId>) -> String {
+        no_hash
+        desc { |tcx|
+            "constructing match decision trees for `{}`",
+            tcx.def_path_str(key.did.to_def_id())
+        }
+    }
+
     /// Set of all the `DefId`s in this crate that have MIR associated with
     /// them. This includes all the body owners, but also things like struct
     /// constructors.
@@ -896,11 +905,26 @@
         desc { "converting literal to const" }
     }
 
-    query check_match(key: DefId) {
+    /// Checks the patterns of a body: the exhaustiveness of its `match`es, the refutability of
+    /// its `let`s and parameters, and so on. Returns `Err` if a match or a refutable pattern was
+    /// reported as non-exhaustive, which would make lowering the body to MIR wrong. Like the
+    /// result, the diagnostics are cached on disk, so a body whose HIR didn't change isn't checked
+    /// again in the next incremental session.
+    query check_match(key: DefId) -> Result<(), ErrorReported> {
         desc { |tcx| "match-checking `{}`", tcx.def_path_str(key) }
         cache_on_disk_if { key.is_local() }
     }
 
+    /// The missing arms of the non-exhaustive `match`es of a body, keyed by the `HirId` of the
+    /// `match` expression and written out as patterns that can be added to it. Unlike
+    /// `check_match`, this doesn't report anything, so tools and lints can use it to fill in or
+    /// reason about the missing arms.
+    query missing_match_arms(key: LocalDefId) -> FxHashMap<hir::HirId, Vec<String>> {
+        storage(ArenaCacheSelector<'tcx>)
+        no_hash
+        desc { |tcx| "computing the missing match arms of `{}`", tcx.def_path_str(key.to_def_id()) }
+    }
+
     /// Performs part of the privacy check and computes "access levels".
     query privacy_access_levels(_: ()) -> &'tcx AccessLevels {
         eval_always
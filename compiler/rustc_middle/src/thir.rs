@@ -752,6 +752,14 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 }
 
                 let num_fields = variant.map_or(subpatterns.len(), |v| v.fields.len());
+                // Spelling out a wildcard for every field only makes the pattern longer, so write
+                // it the way it is usually written. A single field stays `Variant(_)`.
+                if variant.is_some()
+                    && num_fields > 1
+                    && subpatterns.iter().all(|p| matches!(*p.pattern.kind, PatKind::Wild))
+                {
+                    return write!(f, "(..)");
+                }
                 if num_fields != 0 || variant.is_none() {
                     write!(f, "(")?;
                     for i in 0..num_fields {
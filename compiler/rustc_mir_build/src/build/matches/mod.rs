@@ -10,14 +10,16 @@
 use crate::build::ForGuard::{self, OutsideGuard, RefWithinGuard};
 use crate::build::{BlockAnd, BlockAndExtension, Builder};
 use crate::build::{GuardFrame, GuardFrameLocal, LocalsForNode};
+use crate::thir::pattern::match_arm_reachability;
 use rustc_data_structures::{
-    fx::{FxHashSet, FxIndexMap},
+    fx::{FxHashMap, FxHashSet, FxIndexMap},
     stack::ensure_sufficient_stack,
 };
-use rustc_hir::HirId;
+use rustc_hir::{HirId, HirIdMap};
 use rustc_index::bit_set::BitSet;
 use rustc_middle::middle::region;
 use rustc_middle::mir::*;
+use rustc_middle::thir::visit::{self, Visitor};
 use rustc_middle::thir::{self, *};
 use rustc_middle::ty::{self, CanonicalUserTypeAnnotation, Ty};
 use rustc_span::symbol::Symbol;
@@ -160,6 +162,15 @@ pub(crate) fn then_else_break(
             &mut candidates,
         );
 
+        if self.match_trees.is_some() {
+            let tree = self.render_match_tree(span, block, &mut arm_candidates);
+            self.match_trees.as_mut().unwrap().push_str(&tree);
+        }
+        let debugging_opts = &self.tcx.sess.opts.debugging_opts;
+        if debugging_opts.validate_match_lowering || debugging_opts.validate_mir {
+            self.validate_match_lowering(span, block, scrutinee.ty, &mut arm_candidates);
+        }
+
         self.lower_match_arms(
             destination,
             scrutinee_place,
@@ -287,6 +298,139 @@ fn lower_match_tree<'pat>(
         }
     }
 
+    /// Check that match checking agrees with the decision tree that [Builder::lower_match_tree]
+    /// built from `block`, for `-Z validate-match-lowering`. Only exhaustive matches get lowered,
+    /// and the tree may only leave out arms that match checking found unreachable.
+    fn validate_match_lowering(
+        &self,
+        span: Span,
+        block: BasicBlock,
+        scrutinee_ty: Ty<'tcx>,
+        arm_candidates: &mut [(&Arm<'tcx>, Candidate<'_, 'tcx>)],
+    ) {
+        let arms: Vec<_> =
+            arm_candidates.iter().map(|(arm, _)| (&arm.pattern, arm.guard.is_some())).collect();
+        let def_id = self.def_id.expect_local();
+        let (arm_is_reachable, witnesses) =
+            match match_arm_reachability(self.tcx, def_id, self.hir_id, scrutinee_ty, &arms) {
+                Some(result) => result,
+                // The match was too complex for match checking to say anything for sure.
+                None => return,
+            };
+        if let Some(witness) = witnesses.first() {
+            span_bug!(span, "match checking finds `{}` missing from a lowered match", witness);
+        }
+
+        // The blocks the tree can get to. A guard isn't lowered yet, but its failure leads to the
+        // candidate after it.
+        let mut guard_failures = FxHashMap::default();
+        for (_, candidate) in arm_candidates.iter_mut() {
+            candidate.visit_leaves(|leaf_candidate| {
+                if let (Some(pre_binding_block), Some(otherwise_block)) =
+                    (leaf_candidate.pre_binding_block, leaf_candidate.otherwise_block)
+                {
+                    guard_failures.insert(pre_binding_block, otherwise_block);
+                }
+            });
+        }
+        let mut reachable = FxHashSet::default();
+        let mut stack = vec![block];
+        while let Some(block) = stack.pop() {
+            if !reachable.insert(block) {
+                continue;
+            }
+            stack.extend(guard_failures.get(&block).copied());
+            if let Some(terminator) = &self.cfg.block_data(block).terminator {
+                stack.extend(terminator.successors().copied());
+            }
+        }
+
+        for ((arm, candidate), is_reachable) in arm_candidates.iter_mut().zip(arm_is_reachable) {
+            let mut is_in_tree = false;
+            candidate.visit_leaves(|leaf_candidate| {
+                is_in_tree |= leaf_candidate
+                    .pre_binding_block
+                    .map_or(false, |block| reachable.contains(&block));
+            });
+            if is_reachable && !is_in_tree {
+                span_bug!(
+                    arm.span,
+                    "the decision tree never gets to an arm that match checking found reachable"
+                );
+            }
+        }
+    }
+
+    /// Render the decision tree that [Builder::lower_match_tree] built from `block`, labelling
+    /// each leaf with the arm it leads to, for `-Z unpretty=match-decision-tree`.
+    fn render_match_tree(
+        &self,
+        span: Span,
+        block: BasicBlock,
+        arm_candidates: &mut [(&Arm<'tcx>, Candidate<'_, 'tcx>)],
+    ) -> String {
+        let mut leaves = FxHashMap::default();
+        for (index, (arm, candidate)) in arm_candidates.iter_mut().enumerate() {
+            let arm = *arm;
+            candidate.visit_leaves(|leaf_candidate| {
+                if let Some(pre_binding_block) = leaf_candidate.pre_binding_block {
+                    leaves.insert(pre_binding_block, (index, arm));
+                }
+            });
+        }
+
+        let mut out = format!("match at {:?}\n", span);
+        let mut visited = FxHashSet::default();
+        self.render_match_tree_node(&mut out, &leaves, &mut visited, block, 1);
+        out.push('\n');
+        out
+    }
+
+    fn render_match_tree_node(
+        &self,
+        out: &mut String,
+        leaves: &FxHashMap<BasicBlock, (usize, &Arm<'tcx>)>,
+        visited: &mut FxHashSet<BasicBlock>,
+        block: BasicBlock,
+        depth: usize,
+    ) {
+        let indent = "    ".repeat(depth);
+        if let Some((index, arm)) = leaves.get(&block) {
+            out.push_str(&format!("{}{:?}: arm {} `{}`\n", indent, block, index, arm.pattern));
+            return;
+        }
+        if !visited.insert(block) {
+            out.push_str(&format!("{}{:?}: (see above)\n", indent, block));
+            return;
+        }
+        let data = self.cfg.block_data(block);
+        for statement in &data.statements {
+            if let StatementKind::Assign(..) = statement.kind {
+                out.push_str(&format!("{}{:?}: {:?}\n", indent, block, statement));
+            }
+        }
+        let terminator = match &data.terminator {
+            Some(terminator) => terminator,
+            None => return,
+        };
+        let mut head = String::new();
+        terminator.kind.fmt_head(&mut head).unwrap();
+        out.push_str(&format!("{}{:?}: {}\n", indent, block, head));
+        let labels = terminator.kind.fmt_successor_labels();
+        for (label, &successor) in labels.iter().zip(terminator.successors()) {
+            // Unwinding out of a comparison is not a decision.
+            if label == "unwind" {
+                continue;
+            }
+            if label.is_empty() {
+                self.render_match_tree_node(out, leaves, visited, successor, depth + 1);
+            } else {
+                out.push_str(&format!("{}    {} =>\n", indent, label));
+                self.render_match_tree_node(out, leaves, visited, successor, depth + 2);
+            }
+        }
+    }
+
     /// Lower the bindings, guards and arm bodies of a `match` expression.
     ///
     /// The decision tree should have already been created
@@ -1430,13 +1574,13 @@ fn merge_trivial_subcandidates(
     /// and so we know that all remaining match-pairs require some
     /// sort of test. To decide what test to perform, we take the highest
     /// priority candidate (the first one in the list, as of January 2021)
-    /// and extract the first match-pair from the list. From this we decide
+    /// and extract the match-pair whose test is the cheapest from the list,
+    /// going by [`test_cost`](test::test_cost). From this we decide
     /// what kind of test is needed using [`Builder::test`], defined in the
     /// [`test` module](mod@test).
     ///
-    /// *Note:* taking the first match pair is somewhat arbitrary, and
-    /// we might do better here by choosing more carefully what to
-    /// test.
+    /// *Note:* the cost only accounts for the kind of test. Looking at
+    /// how many candidates each test would sort out could do better.
     ///
     /// For example, consider the following possible match-pairs:
     ///
@@ -1554,11 +1698,25 @@ fn test_candidates<'pat, 'b, 'c>(
         otherwise_block: &mut Option<BasicBlock>,
         fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
     ) {
-        // extract the match-pair from the highest priority candidate
-        let match_pair = &candidates.first().unwrap().match_pairs[0];
+        // extract the match-pair from the highest priority candidate, taking the one with the
+        // cheapest test if it has several: the others may then not need to be tested at all
+        let match_pair = candidates
+            .first()
+            .unwrap()
+            .match_pairs
+            .iter()
+            .min_by_key(|match_pair| test::test_cost(match_pair))
+            .unwrap();
         let mut test = self.test(match_pair);
         let match_place = match_pair.place.clone();
 
+        // A run of disjoint ranges is dispatched by bisection rather than tested range by range.
+        if let TestKind::Range(_) = test.kind {
+            if let Some(kind) = self.split_range_test(&match_place, &*candidates) {
+                test.kind = kind;
+            }
+        }
+
         // most of the time, the test to perform is simply a function
         // of the main candidate; but for a test like SwitchInt, we
         // may want to add cases based on the candidates that are
@@ -1746,6 +1904,58 @@ fn calculate_fake_borrows<'b>(
             })
             .collect()
     }
+
+    /// The locals mentioned in `guard`, including those captured by the closures in it.
+    fn guard_locals(&self, guard: &Guard<'tcx>) -> FxHashSet<Local> {
+        let mut visitor = GuardLocalsVisitor {
+            thir: self.thir,
+            var_indices: &self.var_indices,
+            locals: FxHashSet::default(),
+        };
+        match *guard {
+            Guard::If(e) | Guard::IfLet(_, e) => visitor.visit_expr(&self.thir[e]),
+        }
+        visitor.locals
+    }
+}
+
+struct GuardLocalsVisitor<'a, 'b, 'tcx> {
+    thir: &'a Thir<'tcx>,
+    var_indices: &'b HirIdMap<LocalsForNode>,
+    locals: FxHashSet<Local>,
+}
+
+impl<'a, 'b, 'tcx> Visitor<'a, 'tcx> for GuardLocalsVisitor<'a, 'b, 'tcx> {
+    fn thir(&self) -> &'a Thir<'tcx> {
+        self.thir
+    }
+
+    fn visit_expr(&mut self, expr: &Expr<'tcx>) {
+        match expr.kind {
+            ExprKind::VarRef { id } => match self.var_indices.get(&id) {
+                Some(&LocalsForNode::One(local)) => {
+                    self.locals.insert(local);
+                }
+                Some(&LocalsForNode::ForGuard { ref_for_guard, for_arm_body }) => {
+                    self.locals.insert(ref_for_guard);
+                    self.locals.insert(for_arm_body);
+                }
+                None => {}
+            },
+            // Upvars are fields of the closure environment.
+            ExprKind::UpvarRef { .. } => {
+                self.locals.insert(ty::CAPTURE_STRUCT_LOCAL);
+            }
+            // The places a closure captures are not visited by `walk_expr`.
+            ExprKind::Closure { ref upvars, .. } => {
+                for &upvar in upvars.iter() {
+                    self.visit_expr(&self.thir()[upvar]);
+                }
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -1938,6 +2148,15 @@ fn bind_and_guard_matched_candidate<'pat>(
             debug!("entering guard building context: {:?}", guard_frame);
             self.guard_context.push(guard_frame);
 
+            // The guard can only write to the matched places through a local it mentions, so the
+            // places based on other locals don't need to be borrowed.
+            let guard_locals = self.guard_locals(guard);
+            let fake_borrows: &Vec<_> = &fake_borrows
+                .iter()
+                .copied()
+                .filter(|(place, _)| guard_locals.contains(&place.local))
+                .collect();
+
             let re_erased = tcx.lifetimes.re_erased;
             let scrutinee_source_info = self.source_info(scrutinee_span);
             for &(place, temp) in fake_borrows {
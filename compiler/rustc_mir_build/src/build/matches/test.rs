@@ -261,6 +261,12 @@ pub(super) fn perform_test(
                 if !ty.is_scalar() {
                     // Use `PartialEq::eq` instead of `BinOp::Eq`
                     // (the binop can only handle primitives)
+                    //
+                    // String literals get one call per arm rather than a switch on the length
+                    // first. A length test would leave every string pattern to be compared again
+                    // afterwards, but a test must either decide a match pair or split it into
+                    // sub-pairs, and a `&str` has none. `<str as PartialEq>::eq` is inlined in
+                    // optimized builds and compares the lengths before the bytes anyway.
                     self.non_scalar_compare(
                         block,
                         make_target_blocks,
@@ -647,6 +653,12 @@ pub(super) fn sort_candidate<'pat>(
                     return Some(0);
                 }
 
+                if let Some(true) = self.range_contains_range(test, pat) {
+                    // The pattern can be matched only if this test succeeds, but it still has to
+                    // be tested on its own then.
+                    return Some(0);
+                }
+
                 let no_overlap = (|| {
                     use rustc_hir::RangeEnd::*;
                     use std::cmp::Ordering::*;
@@ -678,12 +690,14 @@ pub(super) fn sort_candidate<'pat>(
             }
 
             (&TestKind::Range(range), &PatKind::Constant { value }) => {
-                if let Some(false) = self.const_range_contains(range, value) {
+                match self.const_range_contains(range, value) {
+                    // `value` is contained in the testing range,
+                    // so `value` can be matched only if this test succeeds.
+                    Some(true) => Some(0),
                     // `value` is not contained in the testing range,
                     // so `value` can be matched only if this test fails.
-                    Some(1)
-                } else {
-                    None
+                    Some(false) => Some(1),
+                    None => None,
                 }
             }
 
@@ -791,6 +805,95 @@ fn const_range_contains(
         }
     }
 
+    /// Whether all the values of `inner` are contained in `outer`.
+    fn range_contains_range(&self, outer: PatRange<'tcx>, inner: PatRange<'tcx>) -> Option<bool> {
+        use std::cmp::Ordering::*;
+
+        let tcx = self.tcx;
+        let ty = outer.lo.ty;
+
+        let lo = compare_const_vals(tcx, outer.lo, inner.lo, self.param_env, ty)?;
+        let hi = compare_const_vals(tcx, inner.hi, outer.hi, self.param_env, ty)?;
+
+        Some(
+            lo != Greater
+                && match hi {
+                    Less => true,
+                    Equal => outer.end == RangeEnd::Included || inner.end == RangeEnd::Excluded,
+                    Greater => false,
+                },
+        )
+    }
+
+    /// When the first of `candidates` is tested against a range, and so are a few of the
+    /// following ones against disjoint ranges or constants of an integer or `char` type, returns
+    /// a test that splits them in two halves. Testing them one after the other needs a number of
+    /// tests linear in their count, splitting them recursively only a logarithmic one.
+    pub(super) fn split_range_test(
+        &mut self,
+        test_place: &PlaceBuilder<'tcx>,
+        candidates: &[&mut Candidate<'_, 'tcx>],
+    ) -> Option<TestKind<'tcx>> {
+        use std::cmp::Ordering::*;
+
+        // Below this, a linear chain of range tests is as short as a balanced tree.
+        const MIN_RANGES_TO_SPLIT: usize = 4;
+
+        let mut ranges = vec![];
+        for candidate in candidates {
+            let match_pair = match candidate.match_pairs.iter().find(|mp| mp.place == *test_place)
+            {
+                Some(match_pair) => match_pair,
+                None => break,
+            };
+            match *match_pair.pattern.kind {
+                PatKind::Range(range) => ranges.push(range),
+                PatKind::Constant { value } => {
+                    ranges.push(PatRange { lo: value, hi: value, end: RangeEnd::Included })
+                }
+                _ => break,
+            }
+        }
+        if ranges.len() < MIN_RANGES_TO_SPLIT {
+            return None;
+        }
+
+        let ty = ranges[0].lo.ty;
+        if !(ty.is_integral() || ty.is_char()) {
+            return None;
+        }
+
+        let tcx = self.tcx;
+        let param_env = self.param_env;
+        let mut incomparable = false;
+        ranges.sort_by(|a, b| {
+            compare_const_vals(tcx, a.lo, b.lo, param_env, ty).unwrap_or_else(|| {
+                incomparable = true;
+                Equal
+            })
+        });
+        if incomparable {
+            return None;
+        }
+        // If two ranges overlap, a value can be matched by either one depending on which comes
+        // first, so they can't be sorted into halves.
+        for pair in ranges.windows(2) {
+            match (compare_const_vals(tcx, pair[0].hi, pair[1].lo, param_env, ty)?, pair[0].end) {
+                (Less, _) | (Equal, RangeEnd::Excluded) => {}
+                _ => return None,
+            }
+        }
+
+        // All the ranges before the middle one are below its start, and none of them straddles
+        // it, so this sorts at least one candidate into each outcome of the test.
+        let pivot = ranges[ranges.len() / 2].lo;
+        Some(TestKind::Range(PatRange {
+            lo: ty.numeric_min_val(tcx)?,
+            hi: pivot,
+            end: RangeEnd::Excluded,
+        }))
+    }
+
     fn values_not_contained_in_range(
         &self,
         range: PatRange<'tcx>,
@@ -830,6 +933,21 @@ pub(super) fn targets(&self) -> usize {
     }
 }
 
+/// How expensive the test needed for `match_pair` is, lower is cheaper. A switch on a discriminant
+/// or an integer can decide many candidates at once, while comparing with a string or slice
+/// constant calls `PartialEq::eq`.
+pub(super) fn test_cost(match_pair: &MatchPair<'_, '_>) -> u8 {
+    match *match_pair.pattern.kind {
+        PatKind::Variant { .. } => 0,
+        PatKind::Constant { .. } if is_switch_ty(match_pair.pattern.ty) => 0,
+        PatKind::Range(_) | PatKind::Slice { .. } => 1,
+        PatKind::Constant { .. } if match_pair.pattern.ty.is_scalar() => 1,
+        PatKind::Constant { .. } => 2,
+        // Or-patterns are sorted after everything else, see `test_candidates_with_or`.
+        _ => 3,
+    }
+}
+
 fn is_switch_ty(ty: Ty<'_>) -> bool {
     ty.is_integral() || ty.is_char() || ty.is_bool()
 }
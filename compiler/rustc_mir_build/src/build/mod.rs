@@ -29,7 +29,7 @@
         return tcx.mir_built(def);
     }
 
-    let mut body = mir_build(tcx, def);
+    let mut body = mir_build(tcx, def, None);
     if def.const_param_did.is_some() {
         assert!(matches!(body.source.instance, ty::InstanceDef::Item(_)));
         body.source = MirSource::from_instance(ty::InstanceDef::Item(def.to_global()));
@@ -38,8 +38,24 @@
     tcx.alloc_steal_mir(body)
 }
 
-/// Construct the MIR for a given `DefId`.
-fn mir_build(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> Body<'_> {
+/// Render the decision trees that the matches of a body are lowered to, for
+/// `-Z unpretty=match-decision-tree`.
+crate fn match_decision_tree<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: ty::WithOptConstParam<LocalDefId>,
+) -> String {
+    let mut out = String::new();
+    mir_build(tcx, def, Some(&mut out));
+    out
+}
+
+/// Construct the MIR for a given `DefId`. If `match_trees` is set, the decision trees of its
+/// matches are rendered into it.
+fn mir_build<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: ty::WithOptConstParam<LocalDefId>,
+    match_trees: Option<&mut String>,
+) -> Body<'tcx> {
     let id = tcx.hir().local_def_id_to_hir_id(def.did);
     let body_owner_kind = tcx.hir().body_owner_kind(id);
     let typeck_results = tcx.typeck_opt_const_arg(def);
@@ -100,8 +116,14 @@ fn mir_build(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> Body<'_
     // normal def span.
     let span_with_body = span_with_body.unwrap_or_else(|| tcx.hir().span(id));
 
+    // A body with a non-exhaustive match or refutable pattern must not be lowered as if it were
+    // exhaustive. `check_match` only fails for those, so its other errors don't hide borrowck's.
+    let tainted_by_errors =
+        typeck_results.tainted_by_errors.or_else(|| tcx.check_match(def.did.to_def_id()).err());
+    let rendering_match_trees = match_trees.is_some();
+
     tcx.infer_ctxt().enter(|infcx| {
-        let body = if let Some(ErrorReported) = typeck_results.tainted_by_errors {
+        let body = if let Some(ErrorReported) = tainted_by_errors {
             build::construct_error(&infcx, def, id, body_id, body_owner_kind)
         } else if body_owner_kind.is_fn_or_closure() {
             // fetch the fully liberated fn signature (that is, all bound
@@ -115,10 +137,7 @@ fn mir_build(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> Body<'_
             };
 
             let body = tcx.hir().body(body_id);
-            let (thir, expr) = tcx.thir_body(def);
-            // We ran all queries that depended on THIR at the beginning
-            // of `mir_build`, so now we can steal it
-            let thir = thir.steal();
+            let (thir, expr) = steal_thir(tcx, def, rendering_match_trees);
             let ty = tcx.type_of(fn_def_id);
             let mut abi = fn_sig.abi;
             let implicit_argument = match ty.kind() {
@@ -206,6 +225,7 @@ fn mir_build(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> Body<'_
                 body,
                 expr,
                 span_with_body,
+                match_trees,
             );
             if yield_ty.is_some() {
                 mir.generator.as_mut().unwrap().yield_ty = yield_ty;
@@ -226,15 +246,25 @@ fn mir_build(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> Body<'_
 
             let return_ty = typeck_results.node_type(id);
 
-            let (thir, expr) = tcx.thir_body(def);
-            // We ran all queries that depended on THIR at the beginning
-            // of `mir_build`, so now we can steal it
-            let thir = thir.steal();
+            let (thir, expr) = steal_thir(tcx, def, rendering_match_trees);
 
-            build::construct_const(&thir, &infcx, expr, def, id, return_ty, return_ty_span)
+            build::construct_const(
+                &thir,
+                &infcx,
+                expr,
+                def,
+                id,
+                return_ty,
+                return_ty_span,
+                match_trees,
+            )
         };
 
-        lints::check(tcx, &body);
+        // When we only render the decision trees, the lints were already emitted while building the
+        // body for real.
+        if !rendering_match_trees {
+            lints::check(tcx, &body);
+        }
 
         // The borrow checker will replace all the regions here with its own
         // inference variables. There's no point having non-erased regions here.
@@ -256,6 +286,19 @@ fn mir_build(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> Body<'_
 ///////////////////////////////////////////////////////////////////////////
 // BuildMir -- walks a crate, looking for fn items and methods to build MIR from
 
+/// Take the THIR of `def` to build its MIR from. We ran all queries that depended on THIR at the
+/// beginning of `mir_build`, so we can steal it. When only rendering the decision trees of a body
+/// whose MIR was already built, the THIR is gone, so we build it afresh like `thir_tree` does.
+fn steal_thir<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: ty::WithOptConstParam<LocalDefId>,
+    fresh: bool,
+) -> (Thir<'tcx>, ExprId) {
+    let (thir, expr) =
+        if fresh { crate::thir::cx::thir_body(tcx, def) } else { tcx.thir_body(def) };
+    (thir.steal(), expr)
+}
+
 fn liberated_closure_env_ty(
     tcx: TyCtxt<'_>,
     closure_expr_id: hir::HirId,
@@ -389,6 +432,10 @@ struct Builder<'a, 'tcx> {
     unit_temp: Option<Place<'tcx>>,
 
     var_debug_info: Vec<VarDebugInfo<'tcx>>,
+
+    /// The rendered decision trees of the matches built so far, if they were asked for by
+    /// `-Z unpretty=match-decision-tree`.
+    match_trees: Option<String>,
 }
 
 impl<'a, 'tcx> Builder<'a, 'tcx> {
@@ -604,6 +651,7 @@ fn construct_fn<'tcx, A>(
     body: &'tcx hir::Body<'tcx>,
     expr: ExprId,
     span_with_body: Span,
+    match_trees: Option<&mut String>,
 ) -> Body<'tcx>
 where
     A: Iterator<Item = ArgInfo<'tcx>>,
@@ -625,6 +673,9 @@ fn construct_fn<'tcx, A>(
         return_ty_span,
         body.generator_kind,
     );
+    if match_trees.is_some() {
+        builder.match_trees = Some(String::new());
+    }
 
     let call_site_scope =
         region::Scope { id: body.value.hir_id.local_id, data: region::ScopeData::CallSite };
@@ -662,6 +713,9 @@ fn construct_fn<'tcx, A>(
     };
     debug!("fn_id {:?} has attrs {:?}", fn_def, tcx.get_attrs(fn_def.did.to_def_id()));
 
+    if let Some(out) = match_trees {
+        out.push_str(&builder.match_trees.take().unwrap());
+    }
     let mut body = builder.finish();
     body.spread_arg = spread_arg;
     body
@@ -675,6 +729,7 @@ fn construct_const<'a, 'tcx>(
     hir_id: hir::HirId,
     const_ty: Ty<'tcx>,
     const_ty_span: Span,
+    match_trees: Option<&mut String>,
 ) -> Body<'tcx> {
     let tcx = infcx.tcx;
     let span = tcx.hir().span(hir_id);
@@ -690,6 +745,9 @@ fn construct_const<'a, 'tcx>(
         const_ty_span,
         None,
     );
+    if match_trees.is_some() {
+        builder.match_trees = Some(String::new());
+    }
 
     let mut block = START_BLOCK;
     unpack!(block = builder.expr_into_dest(Place::return_place(), block, &thir[expr]));
@@ -699,6 +757,9 @@ fn construct_const<'a, 'tcx>(
 
     builder.build_drop_trees();
 
+    if let Some(out) = match_trees {
+        out.push_str(&builder.match_trees.take().unwrap());
+    }
     builder.finish()
 }
 
@@ -826,6 +887,7 @@ fn new(
             var_indices: Default::default(),
             unit_temp: None,
             var_debug_info: vec![],
+            match_trees: None,
         };
 
         assert_eq!(builder.cfg.start_new_block(), START_BLOCK);
@@ -24,7 +24,9 @@
 
 pub fn provide(providers: &mut Providers) {
     providers.check_match = thir::pattern::check_match;
+    providers.missing_match_arms = thir::pattern::missing_match_arms;
     providers.lit_to_const = thir::constant::lit_to_const;
+    providers.match_decision_tree = build::match_decision_tree;
     providers.mir_built = build::mir_built;
     providers.thir_check_unsafety = check_unsafety::thir_check_unsafety;
     providers.thir_check_unsafety_for_const_arg = check_unsafety::thir_check_unsafety_for_const_arg;
@@ -9,5 +9,6 @@
 crate mod cx;
 
 crate mod pattern;
+pub use self::pattern::{check_synthetic_match, SyntheticMatchReport};
 
 mod util;
@@ -1,49 +1,277 @@
 use super::usefulness::{
-    compute_match_usefulness, expand_pattern, is_wildcard, MatchArm, MatchCheckCtxt, Reachability,
-    UsefulnessReport,
+    compute_match_usefulness, expand_pattern, is_match_exhaustive, is_wildcard, MatchArm,
+    MatchCheckCtxt, Reachability, UsefulnessReport,
 };
 use super::{PatCtxt, PatternError};
 
 use rustc_arena::TypedArena;
+use rustc_ast::util::parser::PREC_PREFIX;
 use rustc_ast::Mutability;
-use rustc_errors::{error_code, struct_span_err, Applicability, DiagnosticBuilder};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::{
+    error_code, struct_span_err, Applicability, Diagnostic, DiagnosticBuilder, ErrorReported,
+};
 use rustc_hir as hir;
 use rustc_hir::def::*;
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
-use rustc_hir::{HirId, Pat};
-use rustc_middle::thir::PatKind;
+use rustc_hir::{HirId, Pat, CRATE_HIR_ID};
+use rustc_middle::mir::pretty::create_dump_file;
+use rustc_middle::mir::MirSource;
+use rustc_middle::thir::{FieldPat, PatKind};
+use rustc_middle::ty::print::{
+    with_crate_prefix, with_forced_impl_filename_line, with_no_trimmed_paths,
+};
 use rustc_middle::ty::{self, Ty, TyCtxt};
-use rustc_session::lint::builtin::BINDINGS_WITH_VARIANT_NAME;
-use rustc_session::lint::builtin::{IRREFUTABLE_LET_PATTERNS, UNREACHABLE_PATTERNS};
+use rustc_session::lint::builtin::{BINDINGS_WITH_SIMILAR_VARIANT_NAME, BINDINGS_WITH_VARIANT_NAME};
+use rustc_session::lint::builtin::{
+    IRREFUTABLE_LET_PATTERNS, REPEATED_GUARDED_ARMS, UNREACHABLE_PATTERNS,
+};
 use rustc_session::Session;
-use rustc_span::{DesugaringKind, ExpnKind, Span};
-use std::slice;
+use rustc_span::lev_distance::find_best_match_for_name;
+use rustc_span::symbol::{kw, Ident};
+use rustc_span::{sym, BytePos, DesugaringKind, ExpnData, ExpnKind, MacroKind, MultiSpan, Span};
+use std::cell::{Cell, RefCell};
+use std::io::Write;
 
-crate fn check_match(tcx: TyCtxt<'_>, def_id: DefId) {
-    let body_id = match def_id.as_local() {
-        None => return,
-        Some(id) => tcx.hir().body_owned_by(tcx.hir().local_def_id_to_hir_id(id)),
+crate fn check_match(tcx: TyCtxt<'_>, def_id: DefId) -> Result<(), ErrorReported> {
+    let local_def_id = match def_id.as_local() {
+        None => return Ok(()),
+        Some(id) => id,
+    };
+    let body_id = tcx.hir().body_owned_by(tcx.hir().local_def_id_to_hir_id(local_def_id));
+    // The attribute alone can be written by hand, on an impl whose matches were never checked.
+    let is_automatically_derived = tcx.impl_of_method(def_id).map_or(false, |impl_def_id| {
+        tcx.has_attr(impl_def_id, sym::automatically_derived)
+            && matches!(
+                tcx.def_span(impl_def_id).ctxt().outer_expn_data().kind,
+                ExpnKind::Macro(MacroKind::Derive, _)
+            )
+    });
+    with_match_check_ctxt(tcx, local_def_id, |cx| {
+        let mut visitor = MatchVisitor {
+            tcx,
+            typeck_results: tcx.typeck_body(body_id),
+            cx,
+            is_automatically_derived,
+            error: Cell::new(Ok(())),
+        };
+        visitor.visit_body(tcx.hir().body(body_id));
+        visitor.error.get()
+    })
+}
+
+crate fn missing_match_arms(tcx: TyCtxt<'_>, def_id: LocalDefId) -> FxHashMap<HirId, Vec<String>> {
+    let body_id = tcx.hir().body_owned_by(tcx.hir().local_def_id_to_hir_id(def_id));
+    with_silent_match_check_ctxt(tcx, def_id, |cx| {
+        let mut visitor = MissingArmsVisitor {
+            typeck_results: tcx.typeck_body(body_id),
+            cx,
+            missing_arms: FxHashMap::default(),
+        };
+        visitor.visit_body(tcx.hir().body(body_id));
+        visitor.missing_arms
+    })
+}
+
+/// Check the arms of a match of a body again, for `-Z validate-match-lowering` to compare with the
+/// decision tree that MIR building lowers the match to. Returns whether each arm is reachable and
+/// the patterns missing from the match, unless the match is too complex to tell.
+crate fn match_arm_reachability<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    hir_id: HirId,
+    scrut_ty: Ty<'tcx>,
+    arms: &[(&super::Pat<'tcx>, bool)],
+) -> Option<(Vec<bool>, Vec<super::Pat<'tcx>>)> {
+    with_silent_match_check_ctxt(tcx, def_id, |cx| {
+        let arms: Vec<_> = arms
+            .iter()
+            .map(|&(pat, has_guard)| MatchArm {
+                pat: cx.pattern_arena.alloc(expand_pattern(pat.clone())),
+                hir_id,
+                has_guard,
+            })
+            .collect();
+        let report = compute_match_usefulness(&cx, &arms, hir_id, scrut_ty);
+        if report.is_incomplete {
+            return None;
+        }
+        let arm_is_reachable = report
+            .arm_usefulness
+            .iter()
+            .map(|(_, reachability)| matches!(reachability, Reachability::Reachable(_)))
+            .collect();
+        Some((arm_is_reachable, report.non_exhaustiveness_witnesses))
+    })
+}
+
+/// What `check_synthetic_match` found out about a match.
+pub struct SyntheticMatchReport<'tcx> {
+    /// For each arm, whether it is reachable after the arms above it.
+    pub arm_is_reachable: Vec<bool>,
+    /// The patterns missing from the match, empty if it is exhaustive.
+    pub missing_patterns: Vec<super::Pat<'tcx>>,
+    /// Whether the match was too complex to check in full, see `-Z match-check-budget`.
+    pub is_incomplete: bool,
+    /// The lints raised while checking, such as overlapping range endpoints. Their levels are
+    /// those of the crate root. They are left to the caller to emit or inspect.
+    pub lints: Vec<Diagnostic>,
+}
+
+/// Checks a match that doesn't come from any body: `arms` holds the pattern of each arm and
+/// whether it has a guard. This lets fuzzers and other `rustc_private` tools drive match checking
+/// with patterns they built themselves. The patterns must be well-typed for `scrut_ty`.
+/// Uninhabited types are considered from `module`. Nothing is reported: the lints raised while
+/// checking are returned with the rest of the report.
+pub fn check_synthetic_match<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    module: DefId,
+    scrut_ty: Ty<'tcx>,
+    arms: &[(super::Pat<'tcx>, bool)],
+) -> SyntheticMatchReport<'tcx> {
+    let pattern_arena = TypedArena::default();
+    let diagnostics = RefCell::default();
+    let cx = MatchCheckCtxt {
+        tcx,
+        param_env,
+        module,
+        pattern_arena: &pattern_arena,
+        exhaustive_patterns: tcx.features().exhaustive_patterns,
+        uninhabited_cache: &Default::default(),
+        report_cache: &Default::default(),
+        dump: None,
+        diagnostics: &diagnostics,
     };
+    let arms: Vec<_> = arms
+        .iter()
+        .map(|(pat, has_guard)| MatchArm {
+            pat: cx.pattern_arena.alloc(expand_pattern(pat.clone())),
+            hir_id: CRATE_HIR_ID,
+            has_guard: *has_guard,
+        })
+        .collect();
+    let report = compute_match_usefulness(&cx, &arms, CRATE_HIR_ID, scrut_ty);
+    SyntheticMatchReport {
+        arm_is_reachable: report
+            .arm_usefulness
+            .iter()
+            .map(|(_, reachability)| matches!(reachability, Reachability::Reachable(_)))
+            .collect(),
+        missing_patterns: report.non_exhaustiveness_witnesses,
+        is_incomplete: report.is_incomplete,
+        lints: diagnostics.take(),
+    }
+}
+
+/// Build the context for checking the matches of a body, which borrows the arena and caches that
+/// live as long as `f` runs. The diagnostics reported meanwhile are emitted afterwards, sorted by
+/// span like the ones of borrowck, so that their order doesn't depend on the order of the checks.
+fn with_match_check_ctxt<'tcx, R>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    f: impl for<'a> FnOnce(MatchCheckCtxt<'a, 'tcx>) -> R,
+) -> R {
+    let pattern_arena = TypedArena::default();
+    let uninhabited_cache = Default::default();
+    let report_cache = Default::default();
+    let diagnostics = Default::default();
+    let dump = dump_enabled(tcx, def_id.to_def_id()).then(RefCell::default);
+    let result = f(MatchCheckCtxt {
+        tcx,
+        param_env: tcx.param_env(def_id),
+        // Nested bodies are checked on their own, so the whole body is in the same module.
+        module: tcx.parent_module_from_def_id(def_id).to_def_id(),
+        pattern_arena: &pattern_arena,
+        exhaustive_patterns: tcx.features().exhaustive_patterns,
+        uninhabited_cache: &uninhabited_cache,
+        report_cache: &report_cache,
+        dump: dump.as_ref(),
+        diagnostics: &diagnostics,
+    });
+    if let Some(dump) = dump {
+        write_dump(tcx, def_id, &dump.into_inner());
+    }
+    let mut diagnostics = diagnostics.into_inner();
+    diagnostics.sort_by_key(|diag: &Diagnostic| diag.sort_span);
+    for diag in diagnostics {
+        tcx.sess.diagnostic().emit_diagnostic(&diag);
+    }
+    result
+}
 
-    let mut visitor = MatchVisitor {
+/// Like `with_match_check_ctxt`, for the queries that check the matches of a body again to look
+/// at the results. `check_match` already reports on the same body and dumps it, so the diagnostics
+/// reported meanwhile are dropped and nothing is dumped.
+fn with_silent_match_check_ctxt<'tcx, R>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    f: impl for<'a> FnOnce(MatchCheckCtxt<'a, 'tcx>) -> R,
+) -> R {
+    let pattern_arena = TypedArena::default();
+    let uninhabited_cache = Default::default();
+    let report_cache = Default::default();
+    let diagnostics = Default::default();
+    f(MatchCheckCtxt {
         tcx,
-        typeck_results: tcx.typeck_body(body_id),
         param_env: tcx.param_env(def_id),
-        pattern_arena: TypedArena::default(),
+        module: tcx.parent_module_from_def_id(def_id).to_def_id(),
+        pattern_arena: &pattern_arena,
+        exhaustive_patterns: tcx.features().exhaustive_patterns,
+        uninhabited_cache: &uninhabited_cache,
+        report_cache: &report_cache,
+        dump: None,
+        diagnostics: &diagnostics,
+    })
+}
+
+/// Whether `-Z dump-match-check` asks for the matches of this body to be dumped.
+fn dump_enabled(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    let filters = match &tcx.sess.opts.debugging_opts.dump_match_check {
+        None => return false,
+        Some(filters) => filters,
     };
-    visitor.visit_body(tcx.hir().body(body_id));
+    // `def_path_str` could otherwise ask for `type_of`, see `rustc_middle::mir::pretty`.
+    let path = with_forced_impl_filename_line(|| tcx.def_path_str(def_id));
+    filters.split('|').any(|filter| {
+        let filter = filter.trim();
+        filter == "all" || path.contains(filter)
+    })
 }
 
-fn create_e0004(sess: &Session, sp: Span, error_message: String) -> DiagnosticBuilder<'_> {
+/// Writes the `-Z dump-match-check` output of a body to a file in `-Z dump-mir-dir`, named like
+/// the MIR dumps of the body.
+fn write_dump(tcx: TyCtxt<'_>, def_id: LocalDefId, dump: &str) {
+    let source = MirSource::item(def_id.to_def_id());
+    let result = create_dump_file(tcx, "txt", None, "check_match", &"steps", source)
+        .and_then(|mut file| file.write_all(dump.as_bytes()));
+    if let Err(err) = result {
+        tcx.sess.err(&format!("could not write the `-Z dump-match-check` output: {}", err));
+    }
+}
+
+fn create_e0004(
+    sess: &Session,
+    sp: impl Into<MultiSpan>,
+    error_message: String,
+) -> DiagnosticBuilder<'_> {
     struct_span_err!(sess, sp, E0004, "{}", &error_message)
 }
 
 struct MatchVisitor<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
     typeck_results: &'a ty::TypeckResults<'tcx>,
-    param_env: ty::ParamEnv<'tcx>,
-    pattern_arena: TypedArena<super::Pat<'tcx>>,
+    /// The context shared by all the matches and patterns of the body.
+    cx: MatchCheckCtxt<'a, 'tcx>,
+    /// Whether the body is a method of a derived impl. Those contain lots of matches that are
+    /// exhaustive and have no unreachable arms by construction, so only the former is checked.
+    is_automatically_derived: bool,
+    /// Set once a match or a refutable pattern of the body was reported as non-exhaustive (E0004,
+    /// E0005). Those are the only errors that make it wrong to lower the body to MIR. Others, like
+    /// conflicts in `binding @ pattern` or patterns that failed to lower, must not keep borrowck
+    /// from running on the body, and neither must lints, even when they are denied.
+    error: Cell<Result<(), ErrorReported>>,
 }
 
 impl<'tcx> Visitor<'tcx> for MatchVisitor<'_, 'tcx> {
@@ -56,8 +284,10 @@ fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
     fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
         intravisit::walk_expr(self, ex);
         match &ex.kind {
-            hir::ExprKind::Match(scrut, arms, source) => self.check_match(scrut, arms, *source),
-            hir::ExprKind::Let(pat, scrut, span) => self.check_let(pat, scrut, *span),
+            hir::ExprKind::Match(scrut, arms, source) => {
+                self.check_match(ex.span, scrut, arms, *source)
+            }
+            hir::ExprKind::Let(pat, _, span) => self.check_let(pat, *span),
             _ => {}
         }
     }
@@ -68,7 +298,8 @@ fn visit_local(&mut self, loc: &'tcx hir::Local<'tcx>) {
         let (msg, sp) = match loc.source {
             hir::LocalSource::Normal => ("local binding", Some(loc.span)),
             hir::LocalSource::ForLoopDesugar => ("`for` loop binding", None),
-            hir::LocalSource::AsyncFn => ("async fn binding", None),
+            // The pattern is the one the user wrote for a parameter of the `async fn`.
+            hir::LocalSource::AsyncFn => ("function argument", None),
             hir::LocalSource::AwaitDesugar => ("`await` future binding", None),
             hir::LocalSource::AssignDesugar(_) => ("destructuring assignment binding", None),
         };
@@ -78,144 +309,296 @@ fn visit_local(&mut self, loc: &'tcx hir::Local<'tcx>) {
 
     fn visit_param(&mut self, param: &'tcx hir::Param<'tcx>) {
         intravisit::walk_param(self, param);
+        // The parameters of an `async fn` are replaced by plain bindings, and the patterns the
+        // user wrote are checked where the body binds them.
+        if param.pat.span.is_desugaring(DesugaringKind::Async) {
+            return;
+        }
         self.check_irrefutable(&param.pat, "function argument", None);
         self.check_patterns(&param.pat);
     }
 }
 
+/// Collects the missing arms of the matches of a body for `missing_match_arms`, without reporting
+/// anything.
+struct MissingArmsVisitor<'a, 'tcx> {
+    typeck_results: &'a ty::TypeckResults<'tcx>,
+    cx: MatchCheckCtxt<'a, 'tcx>,
+    missing_arms: FxHashMap<HirId, Vec<String>>,
+}
+
+impl<'tcx> Visitor<'tcx> for MissingArmsVisitor<'_, 'tcx> {
+    type Map = intravisit::ErasedMap<'tcx>;
+
+    fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        intravisit::walk_expr(self, ex);
+        if let hir::ExprKind::Match(scrut, arms, hir::MatchSource::Normal) = &ex.kind {
+            if let Some(missing_arms) = self.missing_arms_of(scrut, arms) {
+                self.missing_arms.insert(ex.hir_id, missing_arms);
+            }
+        }
+    }
+}
+
+impl<'tcx> MissingArmsVisitor<'_, 'tcx> {
+    fn missing_arms_of(
+        &self,
+        scrut: &hir::Expr<'_>,
+        arms: &'tcx [hir::Arm<'tcx>],
+    ) -> Option<Vec<String>> {
+        let mut lowered_arms = Vec::with_capacity(arms.len());
+        for arm in arms {
+            let mut patcx = PatCtxt::new(self.cx.tcx, self.cx.param_env, self.typeck_results);
+            patcx.buffer_diagnostics(self.cx.diagnostics);
+            let pattern = patcx.lower_pattern(arm.pat);
+            // Like `check_match`, don't guess what is missing when an arm can't be lowered.
+            if !patcx.errors.is_empty() {
+                return None;
+            }
+            lowered_arms.push(MatchArm {
+                pat: self.cx.pattern_arena.alloc(expand_pattern(pattern)),
+                hir_id: arm.pat.hir_id,
+                has_guard: arm.guard.is_some(),
+            });
+        }
+        let scrut_ty = self.typeck_results.expr_ty_adjusted(scrut);
+        let report = compute_match_usefulness(&self.cx, &lowered_arms, scrut.hir_id, scrut_ty);
+        if report.non_exhaustiveness_witnesses.is_empty() {
+            return None;
+        }
+        let (witnesses, _) = peel_witness_refs(scrut_ty, report.non_exhaustiveness_witnesses);
+        Some(
+            witnesses
+                .iter()
+                .map(|witness| witness_to_source(self.cx.tcx, scrut.hir_id, witness))
+                .collect(),
+        )
+    }
+}
+
 impl PatCtxt<'_, '_> {
-    fn report_inlining_errors(&self) {
+    fn report_inlining_errors(&self, pat: &Pat<'_>) {
         for error in &self.errors {
             match *error {
                 PatternError::StaticInPattern(span) => {
                     self.span_e0158(span, "statics cannot be referenced in patterns")
                 }
                 PatternError::AssocConstInPattern(span) => {
-                    self.span_e0158(span, "associated consts cannot be referenced in patterns")
+                    let mut err = struct_span_err!(
+                        self.tcx.sess,
+                        span,
+                        E0158,
+                        "associated consts cannot be referenced in patterns"
+                    );
+                    self.suggest_guard_comparison(&mut err, pat);
+                    self.emit(err);
                 }
                 PatternError::ConstParamInPattern(span) => {
                     self.span_e0158(span, "const parameters cannot be referenced in patterns")
                 }
                 PatternError::NonConstPath(span) => {
-                    rustc_middle::mir::interpret::struct_error(
-                        self.tcx.at(span),
-                        "runtime values cannot be referenced in patterns",
-                    )
-                    .emit();
+                    let mut err = struct_span_err!(
+                        self.tcx.sess,
+                        span,
+                        E0786,
+                        "runtime values cannot be referenced in patterns"
+                    );
+                    self.suggest_guard_comparison(&mut err, pat);
+                    self.emit(err);
                 }
             }
         }
     }
 
+    /// Values that can't be used in patterns, like runtime values or associated consts that
+    /// depend on generic parameters, can still be compared against in a guard. Suggest turning
+    /// the pattern of an arm without a guard into a binding that the guard checks.
+    fn suggest_guard_comparison(&self, err: &mut DiagnosticBuilder<'_>, pat: &Pat<'_>) {
+        let hir = self.tcx.hir();
+        match hir.find(hir.get_parent_node(pat.hir_id)) {
+            Some(hir::Node::Arm(arm)) if arm.guard.is_none() && arm.pat.hir_id == pat.hir_id => {}
+            _ => return,
+        }
+        if pat.span.from_expansion() {
+            return;
+        }
+        let snippet = match self.tcx.sess.source_map().span_to_snippet(pat.span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        let guard = match pat.kind {
+            hir::PatKind::Range(..) => format!("({}).contains(&value)", snippet),
+            hir::PatKind::Lit(..) | hir::PatKind::Path(..) => format!("value == {}", snippet),
+            _ => return,
+        };
+        err.span_suggestion_verbose(
+            pat.span,
+            "bind the matched value and compare it in a match guard instead",
+            format!("value if {}", guard),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     fn span_e0158(&self, span: Span, text: &str) {
-        struct_span_err!(self.tcx.sess, span, E0158, "{}", text).emit();
+        self.emit(struct_span_err!(self.tcx.sess, span, E0158, "{}", text));
     }
 }
 
-impl<'tcx> MatchVisitor<'_, 'tcx> {
+impl<'a, 'tcx> MatchVisitor<'a, 'tcx> {
     fn check_patterns(&self, pat: &Pat<'_>) {
         pat.walk_always(|pat| check_borrow_conflicts_in_at_patterns(self, pat));
         check_for_bindings_named_same_as_variants(self, pat);
     }
 
-    fn lower_pattern<'p>(
+    fn lower_pattern(
         &self,
-        cx: &mut MatchCheckCtxt<'p, 'tcx>,
         pat: &'tcx hir::Pat<'tcx>,
         have_errors: &mut bool,
-    ) -> (&'p super::Pat<'tcx>, Ty<'tcx>) {
-        let mut patcx = PatCtxt::new(self.tcx, self.param_env, self.typeck_results);
-        patcx.include_lint_checks();
+    ) -> (&'a super::Pat<'tcx>, Ty<'tcx>) {
+        let mut patcx = PatCtxt::new(self.tcx, self.cx.param_env, self.typeck_results);
+        patcx.include_lint_checks().buffer_diagnostics(self.cx.diagnostics);
         let pattern = patcx.lower_pattern(pat);
         let pattern_ty = pattern.ty;
-        let pattern: &_ = cx.pattern_arena.alloc(expand_pattern(pattern));
+        let pattern: &_ = self.cx.pattern_arena.alloc(expand_pattern(pattern));
         if !patcx.errors.is_empty() {
             *have_errors = true;
-            patcx.report_inlining_errors();
+            patcx.report_inlining_errors(pat);
         }
         (pattern, pattern_ty)
     }
 
-    fn new_cx(&self, hir_id: HirId) -> MatchCheckCtxt<'_, 'tcx> {
-        MatchCheckCtxt {
-            tcx: self.tcx,
-            param_env: self.param_env,
-            module: self.tcx.parent_module(hir_id).to_def_id(),
-            pattern_arena: &self.pattern_arena,
-        }
-    }
-
-    fn check_let(&mut self, pat: &'tcx hir::Pat<'tcx>, expr: &hir::Expr<'_>, span: Span) {
+    fn check_let(&mut self, pat: &'tcx hir::Pat<'tcx>, span: Span) {
         self.check_patterns(pat);
-        let mut cx = self.new_cx(expr.hir_id);
-        let tpat = self.lower_pattern(&mut cx, pat, &mut false).0;
-        check_let_reachability(&mut cx, pat.hir_id, &tpat, span);
+        let tpat = self.lower_pattern(pat, &mut false).0;
+        check_let_reachability(&self.cx, pat.hir_id, &tpat, span);
     }
 
     fn check_match(
         &mut self,
+        match_span: Span,
         scrut: &hir::Expr<'_>,
         arms: &'tcx [hir::Arm<'tcx>],
         source: hir::MatchSource,
     ) {
-        let mut cx = self.new_cx(scrut.hir_id);
+        let cx = &self.cx;
 
         for arm in arms {
             // Check the arm for some things unrelated to exhaustiveness.
             self.check_patterns(&arm.pat);
             if let Some(hir::Guard::IfLet(ref pat, _)) = arm.guard {
                 self.check_patterns(pat);
-                let tpat = self.lower_pattern(&mut cx, pat, &mut false).0;
-                check_let_reachability(&mut cx, pat.hir_id, &tpat, tpat.span);
+                let tpat = self.lower_pattern(pat, &mut false).0;
+                check_let_reachability(cx, pat.hir_id, &tpat, tpat.span);
             }
         }
 
         let mut have_errors = false;
 
-        let arms: Vec<_> = arms
+        let hir_arms = arms;
+        // The parts of a pattern that failed to lower are wildcards, so such an arm claims to
+        // cover at least as much as it really does.
+        let all_arms: Vec<_> = arms
             .iter()
-            .map(|hir::Arm { pat, guard, .. }| MatchArm {
-                pat: self.lower_pattern(&mut cx, pat, &mut have_errors).0,
-                hir_id: pat.hir_id,
-                has_guard: guard.is_some(),
+            .map(|hir::Arm { pat, guard, .. }| {
+                let mut arm_has_errors = false;
+                let lowered = self.lower_pattern(pat, &mut arm_has_errors).0;
+                have_errors |= arm_has_errors;
+                let arm = MatchArm { pat: lowered, hir_id: pat.hir_id, has_guard: guard.is_some() };
+                (arm, arm_has_errors)
             })
             .collect();
+        // Leave out the arms whose pattern failed to lower when checking reachability, their
+        // placeholders would make the following arms look unreachable.
+        let arms: Vec<_> =
+            all_arms.iter().filter(|&&(_, has_errors)| !has_errors).map(|&(arm, _)| arm).collect();
 
-        // Bail out early if lowering failed.
-        if have_errors {
+        let scrut_ty = self.typeck_results.expr_ty_adjusted(scrut);
+        if self.is_automatically_derived
+            && !have_errors
+            && is_match_exhaustive(cx, &arms, scrut.hir_id, scrut_ty)
+        {
             return;
         }
+        let report = compute_match_usefulness(cx, &arms, scrut.hir_id, scrut_ty);
+        if report.is_incomplete {
+            match_too_complex(cx, user_facing_span(scrut.span));
+        }
 
-        let scrut_ty = self.typeck_results.expr_ty_adjusted(scrut);
-        let report = compute_match_usefulness(&cx, &arms, scrut.hir_id, scrut_ty);
-
-        report_arm_reachability(&cx, &report, |_, arm_span, arm_hir_id, catchall| {
+        report_arm_reachability(cx, &report, |_, arm_span, arm_hir_id, catchall| {
             match source {
                 hir::MatchSource::ForLoopDesugar | hir::MatchSource::Normal => {
-                    unreachable_pattern(cx.tcx, arm_span, arm_hir_id, catchall);
+                    unreachable_pattern(cx, arm_span, arm_hir_id, catchall);
                 }
                 // Unreachable patterns in try and await expressions occur when one of
                 // the arms are an uninhabited type. Which is OK.
                 hir::MatchSource::AwaitDesugar | hir::MatchSource::TryDesugar => {}
             }
         });
+        if let hir::MatchSource::Normal = source {
+            check_for_repeated_guarded_arms(
+                cx,
+                self.typeck_results,
+                hir_arms,
+                &all_arms,
+                &report,
+            );
+        }
 
         // Check if the match is exhaustive.
-        // Note: An empty match isn't the same as an empty matrix for diagnostics purposes,
-        // since an empty matrix can occur when there are arms, if those arms all have guards.
-        let is_empty_match = arms.is_empty();
+        // Leaving arms out can only make the remaining ones look more reachable, so the lints
+        // above are still sound. Exhaustiveness is checked with the placeholders put back: the
+        // values they miss are missed by the match whatever the erroneous patterns meant.
+        let (arms, report) = if have_errors {
+            let arms: Vec<_> = all_arms.iter().map(|&(arm, _)| arm).collect();
+            let report = compute_match_usefulness(cx, &arms, scrut.hir_id, scrut_ty);
+            (arms, report)
+        } else {
+            (arms, report)
+        };
+        let witnesses_truncated = report.witnesses_truncated;
         let witnesses = report.non_exhaustiveness_witnesses;
         if !witnesses.is_empty() {
-            non_exhaustive_match(&cx, scrut_ty, scrut.span, witnesses, is_empty_match);
+            // Check whether the match is only rejected because uninhabited types aren't reasoned
+            // about without the feature gate. Lints emitted again by this second run are identical
+            // to the first ones, so they get deduplicated.
+            let exhaustive_with_gate = !cx.exhaustive_patterns
+                && cx.tcx.sess.is_nightly_build()
+                && {
+                    let gated_cx = MatchCheckCtxt { exhaustive_patterns: true, ..*cx };
+                    compute_match_usefulness(&gated_cx, &arms, scrut.hir_id, scrut_ty)
+                        .non_exhaustiveness_witnesses
+                        .is_empty()
+                };
+            non_exhaustive_match(
+                cx,
+                scrut_ty,
+                match_span,
+                scrut,
+                hir_arms,
+                witnesses,
+                witnesses_truncated,
+                exhaustive_with_gate,
+            );
+            self.error.set(Err(ErrorReported));
         }
     }
 
     fn check_irrefutable(&self, pat: &'tcx Pat<'tcx>, origin: &str, sp: Option<Span>) {
-        let mut cx = self.new_cx(pat.hir_id);
+        let cx = &self.cx;
 
-        let (pattern, pattern_ty) = self.lower_pattern(&mut cx, pat, &mut false);
+        let (pattern, pattern_ty) = self.lower_pattern(pat, &mut false);
         let arms = vec![MatchArm { pat: pattern, hir_id: pat.hir_id, has_guard: false }];
-        let report = compute_match_usefulness(&cx, &arms, pat.hir_id, pattern_ty);
+        if self.is_automatically_derived && is_match_exhaustive(cx, &arms, pat.hir_id, pattern_ty) {
+            return;
+        }
+        let report = compute_match_usefulness(cx, &arms, pat.hir_id, pattern_ty);
+        if report.is_incomplete {
+            match_too_complex(cx, user_facing_span(pat.span));
+        }
 
         // Note: we ignore whether the pattern is unreachable (i.e. whether the type is empty). We
         // only care about exhaustiveness here.
@@ -224,11 +607,13 @@ fn check_irrefutable(&self, pat: &'tcx Pat<'tcx>, origin: &str, sp: Option<Span>
             // The pattern is irrefutable.
             return;
         }
+        let (witnesses, peeled_refs) = peel_witness_refs(pattern_ty, witnesses);
+        let pat_span = user_facing_span(pat.span);
 
-        let joined_patterns = joined_uncovered_patterns(&witnesses);
+        let joined_patterns = joined_uncovered_patterns(&witnesses, report.witnesses_truncated);
         let mut err = struct_span_err!(
             self.tcx.sess,
-            pat.span,
+            pat_span,
             E0005,
             "refutable pattern in {}: {} not covered",
             origin,
@@ -242,7 +627,7 @@ fn check_irrefutable(&self, pat: &'tcx Pat<'tcx>, origin: &str, sp: Option<Span>
                 false
             }
             _ => {
-                err.span_label(pat.span, pattern_not_covered_label(&witnesses, &joined_patterns));
+                err.span_label(pat_span, pattern_not_covered_label(&witnesses, &joined_patterns));
                 true
             }
         };
@@ -266,9 +651,13 @@ fn check_irrefutable(&self, pat: &'tcx Pat<'tcx>, origin: &str, sp: Option<Span>
             );
         }
 
-        adt_defined_here(&cx, &mut err, pattern_ty, &witnesses);
+        adt_defined_here(cx, &mut err, pattern_ty, &witnesses);
         err.note(&format!("the matched value is of type `{}`", pattern_ty));
-        err.emit();
+        if peeled_refs {
+            err.note(PEELED_REFS_NOTE);
+        }
+        cx.buffer(err);
+        self.error.set(Err(ErrorReported));
     }
 }
 
@@ -303,38 +692,56 @@ fn const_not_var(
 
 fn check_for_bindings_named_same_as_variants(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_>) {
     pat.walk_always(|p| {
-        if let hir::PatKind::Binding(_, _, ident, None) = p.kind {
+        if let hir::PatKind::Binding(_, _, ident, sub) = p.kind {
             if let Some(ty::BindByValue(hir::Mutability::Not)) =
                 cx.typeck_results.extract_binding_mode(cx.tcx.sess, p.hir_id, p.span)
             {
                 let pat_ty = cx.typeck_results.pat_ty(p).peel_refs();
                 if let ty::Adt(edef, _) = pat_ty.kind() {
-                    if edef.is_enum()
-                        && edef.variants.iter().any(|variant| {
-                            variant.ident == ident && variant.ctor_kind == CtorKind::Const
-                        })
-                    {
+                    if !edef.is_enum() {
+                        return;
+                    }
+                    if edef.variants.iter().any(|variant| {
+                        variant.ident == ident && variant.ctor_kind == CtorKind::Const
+                    }) {
                         cx.tcx.struct_span_lint_hir(
                             BINDINGS_WITH_VARIANT_NAME,
                             p.hir_id,
-                            p.span,
+                            ident.span,
                             |lint| {
-                                let ty_path = cx.tcx.def_path_str(edef.did);
-                                lint.build(&format!(
+                                let mut err = lint.build(&format!(
                                     "pattern binding `{}` is named the same as one \
                                                 of the variants of the type `{}`",
-                                    ident, ty_path
-                                ))
-                                .code(error_code!(E0170))
-                                .span_suggestion(
-                                    p.span,
-                                    "to match on the variant, qualify the path",
-                                    format!("{}::{}", ty_path, ident),
-                                    Applicability::MachineApplicable,
-                                )
-                                .emit();
+                                    ident,
+                                    cx.tcx.def_path_str(edef.did)
+                                ));
+                                err.code(error_code!(E0170));
+                                // A path can't have a `@` sub-pattern, so only suggest matching on
+                                // the variant if there is none.
+                                if sub.is_none() {
+                                    let (ty_path, applicability) =
+                                        variant_suggestion_path(cx.tcx, p.hir_id, edef);
+                                    err.span_suggestion(
+                                        ident.span,
+                                        "to match on the variant, qualify the path",
+                                        format!("{}::{}", ty_path, ident),
+                                        applicability,
+                                    );
+                                }
+                                let new_name = ident.as_str().to_lowercase();
+                                if new_name != *ident.as_str() {
+                                    err.span_suggestion(
+                                        ident.span,
+                                        "if you meant to introduce a new binding, rename it",
+                                        new_name,
+                                        Applicability::MaybeIncorrect,
+                                    );
+                                }
+                                cx.cx.buffer(err);
                             },
                         )
+                    } else if sub.is_none() && ident.as_str().starts_with(char::is_uppercase) {
+                        check_for_binding_named_like_variant(cx, p.hir_id, ident, edef);
                     }
                 }
             }
@@ -342,30 +749,140 @@ fn check_for_bindings_named_same_as_variants(cx: &MatchVisitor<'_, '_>, pat: &Pa
     });
 }
 
+/// A binding that is capitalized like a variant and whose name is close to one of the unit
+/// variants of the matched `enum` is most likely a misspelling of that variant.
+fn check_for_binding_named_like_variant(
+    cx: &MatchVisitor<'_, '_>,
+    hir_id: HirId,
+    ident: Ident,
+    edef: &ty::AdtDef,
+) {
+    let names: Vec<_> = edef
+        .variants
+        .iter()
+        .filter(|variant| variant.ctor_kind == CtorKind::Const)
+        .map(|variant| variant.ident.name)
+        .collect();
+    let similar = match find_best_match_for_name(&names, ident.name, None) {
+        Some(similar) => similar,
+        None => return,
+    };
+    cx.tcx.struct_span_lint_hir(BINDINGS_WITH_SIMILAR_VARIANT_NAME, hir_id, ident.span, |lint| {
+        let (ty_path, _) = variant_suggestion_path(cx.tcx, hir_id, edef);
+        let mut err = lint.build(&format!(
+            "pattern binding `{}` is named similarly to the variant `{}::{}`",
+            ident, ty_path, similar
+        ));
+        err.span_suggestion(
+            ident.span,
+            "to match on the variant, qualify the path",
+            format!("{}::{}", ty_path, similar),
+            Applicability::MaybeIncorrect,
+        );
+        cx.cx.buffer(err);
+    });
+}
+
+/// Returns a path to the `enum` that can be written where `hir_id` is, and how sure we are of it.
+///
+/// The trimmed path isn't good enough here: the `enum` could be defined in a different module, or
+/// imported under another name, or shadowed. A name that the current module declares or imports
+/// is in scope. Otherwise we fall back to the full path, which may go through a private module.
+fn variant_suggestion_path(
+    tcx: TyCtxt<'_>,
+    hir_id: HirId,
+    edef: &ty::AdtDef,
+) -> (String, Applicability) {
+    let module = tcx.parent_module(hir_id);
+    if let Some(local_did) = edef.did.as_local() {
+        if tcx.parent_module(tcx.hir().local_def_id_to_hir_id(local_did)) == module {
+            // The `enum` is declared in the current module, so its name is in scope.
+            return (tcx.item_name(edef.did).to_string(), Applicability::MachineApplicable);
+        }
+    }
+    let (module_items, ..) = tcx.hir().get_module(module);
+    for &item_id in module_items.item_ids {
+        let item = tcx.hir().item(item_id);
+        if let hir::ItemKind::Use(path, hir::UseKind::Single) = item.kind {
+            // `use Enum as _` doesn't bring a name into scope.
+            if path.res == Res::Def(DefKind::Enum, edef.did) && item.ident.name != kw::Underscore
+            {
+                return (item.ident.to_string(), Applicability::MachineApplicable);
+            }
+        }
+    }
+    let path = with_crate_prefix(|| with_no_trimmed_paths(|| tcx.def_path_str(edef.did)));
+    let path = if edef.did.is_local() && !tcx.sess.rust_2018() {
+        // In Rust 2015, paths are relative to the current module.
+        format!("::{}", path)
+    } else {
+        path
+    };
+    (path, Applicability::MaybeIncorrect)
+}
+
 /// Checks for common cases of "catchall" patterns that may not be intended as such.
-fn pat_is_catchall(pat: &super::Pat<'_>) -> bool {
+fn pat_is_catchall<'p, 'tcx>(
+    cx: &MatchCheckCtxt<'p, 'tcx>,
+    hir_id: HirId,
+    pat: &'p super::Pat<'tcx>,
+) -> bool {
     use PatKind::*;
     match &*pat.kind {
         Binding { subpattern: None, .. } => true,
-        Binding { subpattern: Some(s), .. } | Deref { subpattern: s } => pat_is_catchall(s),
-        Leaf { subpatterns: s } => s.iter().all(|p| pat_is_catchall(&p.pattern)),
+        Binding { subpattern: Some(s), .. } | Deref { subpattern: s } => {
+            pat_is_catchall(cx, hir_id, s)
+        }
+        Leaf { subpatterns: s } => s.iter().all(|p| pat_is_catchall(cx, hir_id, &p.pattern)),
+        // Alternatives like `Some(_) | None` or a range like `0..=u8::MAX` can cover the whole
+        // type without any of their parts doing so on its own.
+        Or { .. } | Range(..) => {
+            let arms = [MatchArm { pat, hir_id, has_guard: false }];
+            is_match_exhaustive(cx, &arms, hir_id, pat.ty)
+        }
         _ => false,
     }
 }
 
-fn unreachable_pattern(tcx: TyCtxt<'_>, span: Span, id: HirId, catchall: Option<Span>) {
-    tcx.struct_span_lint_hir(UNREACHABLE_PATTERNS, id, span, |lint| {
+fn unreachable_pattern(cx: &MatchCheckCtxt<'_, '_>, span: Span, id: HirId, catchall: Option<Span>) {
+    let span = user_facing_span(span);
+    let catchall = catchall.map(user_facing_span);
+    cx.tcx.struct_span_lint_hir(UNREACHABLE_PATTERNS, id, span, |lint| {
         let mut err = lint.build("unreachable pattern");
         if let Some(catchall) = catchall {
             // We had a catchall pattern, hint at that.
             err.span_label(span, "unreachable pattern");
             err.span_label(catchall, "matches any value");
         }
-        err.emit();
+        cx.buffer(err);
     });
 }
 
-fn irrefutable_let_pattern(tcx: TyCtxt<'_>, id: HirId, span: Span) {
+/// Warn that we gave up on checking a match, and explain what we assumed instead.
+fn match_too_complex(cx: &MatchCheckCtxt<'_, '_>, span: Span) {
+    let msg = "this `match` is too complex to fully analyze";
+    let mut err = cx.tcx.sess.struct_span_warn(span, msg);
+    err.note(
+        "all of its arms are assumed to be reachable, and it is only considered exhaustive if it \
+         has a `_` arm",
+    )
+    .help("split it into smaller matches, or raise the limit with `-Z match-check-budget`");
+    cx.buffer(err);
+}
+
+/// The patterns and scrutinees introduced when lowering `async fn` and `.await` have spans inside
+/// the desugaring. Report those at the `async fn` parameter or `.await` expression instead.
+fn user_facing_span(span: Span) -> Span {
+    match span.desugaring_kind() {
+        Some(DesugaringKind::Async | DesugaringKind::Await) => {
+            span.ctxt().outer_expn_data().call_site
+        }
+        _ => span,
+    }
+}
+
+fn irrefutable_let_pattern(cx: &MatchCheckCtxt<'_, '_>, id: HirId, span: Span) {
+    let tcx = cx.tcx;
     macro_rules! emit_diag {
         (
             $lint:expr,
@@ -376,7 +893,7 @@ macro_rules! emit_diag {
             let mut diag = $lint.build(concat!("irrefutable ", $source_name, " pattern"));
             diag.note(concat!("this pattern will always match, so the ", $note_sufix));
             diag.help(concat!("consider ", $help_sufix));
-            diag.emit()
+            cx.buffer(diag);
         }};
     }
 
@@ -390,12 +907,16 @@ macro_rules! emit_diag {
             emit_diag!(lint, "`let`", "`let` is useless", "removing `let`");
         }
         LetSource::IfLet => {
-            emit_diag!(
-                lint,
-                "`if let`",
-                "`if let` is useless",
-                "replacing the `if let` with a `let`"
-            );
+            let mut diag = lint.build("irrefutable `if let` pattern");
+            diag.note("this pattern will always match, so the `if let` is useless");
+            let msg = "consider replacing the `if let` with a `let`";
+            match if_let_to_let_suggestion(tcx, id) {
+                Some(suggestion) => {
+                    diag.multipart_suggestion(msg, suggestion, Applicability::MaybeIncorrect)
+                }
+                None => diag.help(msg),
+            };
+            cx.buffer(diag);
         }
         LetSource::IfLetGuard => {
             emit_diag!(
@@ -424,28 +945,61 @@ macro_rules! emit_diag {
     });
 }
 
+/// Rewrite an irrefutable `if let PAT = EXPR { .. }` into `let PAT = EXPR; { .. }`. This is only
+/// possible without an `else` branch, and when the `if let` is a statement or the tail of a block,
+/// so that the value of the block it turns into doesn't matter.
+///
+/// The result can still change what the code means. The bindings now live until the end of the
+/// enclosing block, where they can shadow later names, and the temporaries of the scrutinee are
+/// dropped before the block runs instead of after it. So the suggestion is only `MaybeIncorrect`.
+fn if_let_to_let_suggestion(tcx: TyCtxt<'_>, pat_id: HirId) -> Option<Vec<(Span, String)>> {
+    let hir = tcx.hir();
+    let let_id = hir.get_parent_node(pat_id);
+    let let_expr = hir.expect_expr(let_id);
+    let if_id = hir.get_parent_node(let_id);
+    let if_expr = match hir.get(if_id) {
+        hir::Node::Expr(expr @ hir::Expr { kind: hir::ExprKind::If(cond, _, None), .. })
+            if cond.hir_id == let_id =>
+        {
+            expr
+        }
+        _ => return None,
+    };
+    if if_expr.span.from_expansion() || let_expr.span.from_expansion() {
+        return None;
+    }
+    match hir.get(hir.get_parent_node(if_id)) {
+        hir::Node::Stmt(_) | hir::Node::Block(_) => {}
+        _ => return None,
+    }
+    Some(vec![
+        (if_expr.span.until(let_expr.span), String::new()),
+        (let_expr.span.shrink_to_hi(), ";".to_string()),
+    ])
+}
+
 fn check_let_reachability<'p, 'tcx>(
-    cx: &mut MatchCheckCtxt<'p, 'tcx>,
+    cx: &MatchCheckCtxt<'p, 'tcx>,
     pat_id: HirId,
     pat: &'p super::Pat<'tcx>,
     span: Span,
 ) {
     let arms = [MatchArm { pat, hir_id: pat_id, has_guard: false }];
-    let report = compute_match_usefulness(&cx, &arms, pat_id, pat.ty);
+    let report = compute_match_usefulness(cx, &arms, pat_id, pat.ty);
 
-    report_arm_reachability(&cx, &report, |arm_index, arm_span, arm_hir_id, _| {
+    report_arm_reachability(cx, &report, |arm_index, arm_span, arm_hir_id, _| {
         match let_source(cx.tcx, pat_id) {
             LetSource::IfLet | LetSource::WhileLet => {
                 match arm_index {
                     // The arm with the user-specified pattern.
-                    0 => unreachable_pattern(cx.tcx, arm_span, arm_hir_id, None),
+                    0 => unreachable_pattern(cx, arm_span, arm_hir_id, None),
                     // The arm with the wildcard pattern.
-                    1 => irrefutable_let_pattern(cx.tcx, pat_id, arm_span),
+                    1 => irrefutable_let_pattern(cx, pat_id, arm_span),
                     _ => bug!(),
                 }
             }
             LetSource::IfLetGuard if arm_index == 0 => {
-                unreachable_pattern(cx.tcx, arm_span, arm_hir_id, None);
+                unreachable_pattern(cx, arm_span, arm_hir_id, None);
             }
             _ => {}
         }
@@ -453,7 +1007,7 @@ fn check_let_reachability<'p, 'tcx>(
 
     if report.non_exhaustiveness_witnesses.is_empty() {
         // The match is exhaustive, i.e. the `if let` pattern is irrefutable.
-        irrefutable_let_pattern(cx.tcx, pat_id, span);
+        irrefutable_let_pattern(cx, pat_id, span);
     }
 }
 
@@ -477,24 +1031,223 @@ fn report_arm_reachability<'p, 'tcx, F>(
                 // Emit lints in the order in which they occur in the file.
                 unreachables.sort_unstable();
                 for span in unreachables {
-                    unreachable_pattern(cx.tcx, span, arm.hir_id, None);
+                    unreachable_pattern(cx, span, arm.hir_id, None);
                 }
             }
         }
-        if !arm.has_guard && catchall.is_none() && pat_is_catchall(arm.pat) {
+        if !arm.has_guard && catchall.is_none() && pat_is_catchall(cx, arm.hir_id, arm.pat) {
             catchall = Some(arm.pat.span);
         }
     }
 }
 
+/// Reports guarded arms that repeat the pattern and the guard of an earlier arm. Reachability
+/// ignores guarded arms, but if the guard has no side effects such an arm can only be tried after
+/// the same guard already failed for the same values.
+fn check_for_repeated_guarded_arms<'p, 'tcx>(
+    cx: &MatchCheckCtxt<'p, 'tcx>,
+    typeck_results: &ty::TypeckResults<'tcx>,
+    hir_arms: &[hir::Arm<'_>],
+    arms: &[(MatchArm<'p, 'tcx>, bool)],
+    report: &UsefulnessReport<'p, 'tcx>,
+) {
+    // Arms that are unreachable whatever their guard have been reported already.
+    let unreachable: FxHashSet<HirId> = report
+        .arm_usefulness
+        .iter()
+        .filter(|(_, reachability)| matches!(reachability, Reachability::Unreachable))
+        .map(|(arm, _)| arm.hir_id)
+        .collect();
+    let guarded_arms: Vec<_> = hir_arms
+        .iter()
+        .zip(arms)
+        .filter_map(|(hir_arm, &(arm, has_errors))| match hir_arm.guard {
+            Some(hir::Guard::If(guard)) if !has_errors && !hir_arm.span.from_expansion() => {
+                Some((hir_arm, guard, arm.pat))
+            }
+            _ => None,
+        })
+        .collect();
+
+    for (i, (hir_arm, guard, pat)) in guarded_arms.iter().enumerate() {
+        if unreachable.contains(&hir_arm.pat.hir_id) {
+            continue;
+        }
+        let earlier = guarded_arms[..i].iter().find(|(_, earlier_guard, earlier_pat)| {
+            pats_are_equal(earlier_pat, pat)
+                && guards_are_equal(typeck_results, earlier_guard, guard)
+        });
+        let (earlier_arm, earlier_guard, _) = match earlier {
+            Some(earlier) => earlier,
+            None => continue,
+        };
+
+        // Take the comma ending the arm along, if any, so that the remaining arms still parse.
+        let mut removal_span = hir_arm.span;
+        if let Ok(next) = cx.tcx.sess.source_map().span_to_next_source(hir_arm.span) {
+            let trimmed = next.trim_start();
+            if trimmed.starts_with(',') {
+                let comma_end = (next.len() - trimmed.len() + 1) as u32;
+                removal_span = removal_span.with_hi(removal_span.hi() + BytePos(comma_end));
+            }
+        }
+        cx.tcx.struct_span_lint_hir(
+            REPEATED_GUARDED_ARMS,
+            hir_arm.pat.hir_id,
+            hir_arm.pat.span,
+            |lint| {
+                let mut err =
+                    lint.build("this arm repeats the pattern and guard of an earlier arm");
+                err.span_label(
+                    earlier_arm.pat.span.to(earlier_guard.span),
+                    "this arm has the same pattern and guard",
+                );
+                err.span_suggestion(
+                    removal_span,
+                    "remove this arm",
+                    String::new(),
+                    Applicability::MaybeIncorrect,
+                );
+                cx.buffer(err);
+            },
+        );
+    }
+}
+
+/// Whether two patterns have the same structure and bind the same names in the same places.
+/// Spans are ignored, so that the patterns of different arms can be equal.
+fn pats_are_equal<'tcx>(a: &super::Pat<'tcx>, b: &super::Pat<'tcx>) -> bool {
+    let all_equal = |a: &[super::Pat<'tcx>], b: &[super::Pat<'tcx>]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| pats_are_equal(a, b))
+    };
+    let opt_equal = |a: &Option<super::Pat<'tcx>>, b: &Option<super::Pat<'tcx>>| match (a, b) {
+        (Some(a), Some(b)) => pats_are_equal(a, b),
+        (None, None) => true,
+        _ => false,
+    };
+    let fields_equal = |a: &[FieldPat<'tcx>], b: &[FieldPat<'tcx>]| {
+        a.len() == b.len()
+            && a.iter().zip(b).all(|(a, b)| {
+                a.field == b.field && pats_are_equal(&a.pattern, &b.pattern)
+            })
+    };
+    match (&*a.kind, &*b.kind) {
+        (PatKind::Wild, PatKind::Wild) => true,
+        (
+            PatKind::AscribeUserType { subpattern: a, .. },
+            PatKind::AscribeUserType { subpattern: b, .. },
+        ) => pats_are_equal(a, b),
+        (
+            PatKind::Binding { mutability: a_mut, name: a_name, mode: a_mode, subpattern: a, .. },
+            PatKind::Binding { mutability: b_mut, name: b_name, mode: b_mode, subpattern: b, .. },
+        ) => a_mut == b_mut && a_name == b_name && a_mode == b_mode && opt_equal(a, b),
+        (
+            PatKind::Variant { adt_def: a_adt, variant_index: a_idx, subpatterns: a, .. },
+            PatKind::Variant { adt_def: b_adt, variant_index: b_idx, subpatterns: b, .. },
+        ) => a_adt.did == b_adt.did && a_idx == b_idx && fields_equal(a, b),
+        (PatKind::Leaf { subpatterns: a }, PatKind::Leaf { subpatterns: b }) => fields_equal(a, b),
+        (PatKind::Deref { subpattern: a }, PatKind::Deref { subpattern: b }) => {
+            pats_are_equal(a, b)
+        }
+        (PatKind::Constant { value: a }, PatKind::Constant { value: b }) => a == b,
+        (PatKind::Range(a), PatKind::Range(b)) => a == b,
+        (
+            PatKind::Slice { prefix: a_prefix, slice: a_slice, suffix: a_suffix },
+            PatKind::Slice { prefix: b_prefix, slice: b_slice, suffix: b_suffix },
+        )
+        | (
+            PatKind::Array { prefix: a_prefix, slice: a_slice, suffix: a_suffix },
+            PatKind::Array { prefix: b_prefix, slice: b_slice, suffix: b_suffix },
+        ) => {
+            all_equal(a_prefix, b_prefix)
+                && opt_equal(a_slice, b_slice)
+                && all_equal(a_suffix, b_suffix)
+        }
+        (PatKind::Or { pats: a }, PatKind::Or { pats: b }) => all_equal(a, b),
+        _ => false,
+    }
+}
+
+/// Whether two guards are the same expression, made only of built-in operations without side
+/// effects. Bindings are compared by name, which is enough since the arms have equal patterns.
+/// Overloaded operators and implicit adjustments such as overloaded derefs run user code, so
+/// expressions with either are never equal.
+fn guards_are_equal<'tcx>(
+    typeck_results: &ty::TypeckResults<'tcx>,
+    a: &hir::Expr<'_>,
+    b: &hir::Expr<'_>,
+) -> bool {
+    use hir::ExprKind::*;
+    let runs_user_code = |expr: &hir::Expr<'_>| {
+        typeck_results.is_method_call(expr) || !typeck_results.expr_adjustments(expr).is_empty()
+    };
+    if runs_user_code(a) || runs_user_code(b) {
+        return false;
+    }
+    match (&a.kind, &b.kind) {
+        (Lit(a), Lit(b)) => a.node == b.node,
+        (Path(hir::QPath::Resolved(None, a)), Path(hir::QPath::Resolved(None, b))) => {
+            match (a.res, b.res) {
+                (Res::Local(_), Res::Local(_)) => {
+                    let name = |path: &hir::Path<'_>| path.segments.last().map(|s| s.ident.name);
+                    name(a) == name(b)
+                }
+                (a, b) => a == b,
+            }
+        }
+        (Field(a, a_field), Field(b, b_field)) => {
+            a_field.name == b_field.name && guards_are_equal(typeck_results, a, b)
+        }
+        (Binary(a_op, a_lhs, a_rhs), Binary(b_op, b_lhs, b_rhs)) => {
+            a_op.node == b_op.node
+                && guards_are_equal(typeck_results, a_lhs, b_lhs)
+                && guards_are_equal(typeck_results, a_rhs, b_rhs)
+        }
+        (Unary(a_op, a), Unary(b_op, b)) => a_op == b_op && guards_are_equal(typeck_results, a, b),
+        (AddrOf(a_kind, a_mutbl, a), AddrOf(b_kind, b_mutbl, b)) => {
+            a_kind == b_kind && a_mutbl == b_mutbl && guards_are_equal(typeck_results, a, b)
+        }
+        _ => false,
+    }
+}
+
 /// Report that a match is not exhaustive.
 fn non_exhaustive_match<'p, 'tcx>(
     cx: &MatchCheckCtxt<'p, 'tcx>,
     scrut_ty: Ty<'tcx>,
-    sp: Span,
+    match_span: Span,
+    scrut: &hir::Expr<'_>,
+    arms: &[hir::Arm<'_>],
     witnesses: Vec<super::Pat<'tcx>>,
-    is_empty_match: bool,
+    witnesses_truncated: bool,
+    exhaustive_with_gate: bool,
 ) {
+    // A scrutinee written inside a macro would point into the macro's definition, so point at the
+    // invocation instead. This also makes the errors for several identical `match`es from one
+    // invocation identical, and only the first of those is emitted.
+    let outermost_macro = scrut
+        .span
+        .macro_backtrace()
+        .filter(|expn_data| matches!(expn_data.kind, ExpnKind::Macro(..)))
+        .last();
+    let sp = match &outermost_macro {
+        Some(expn_data) => expn_data.call_site,
+        None => user_facing_span(scrut.span),
+    };
+    let (witnesses, peeled_refs) = peel_witness_refs(scrut_ty, witnesses);
+    // Highlight the `match` keyword along with the scrutinee, so that the head of the `match`
+    // reads as one unit. The scrutinee comes first, as the location of the error.
+    let mut primary_spans = vec![sp];
+    let match_keyword = cx.tcx.sess.source_map().span_until_whitespace(match_span);
+    if !match_span.from_expansion()
+        && cx.tcx.sess.source_map().span_to_snippet(match_keyword).map_or(false, |s| s == "match")
+    {
+        primary_spans.push(match_keyword);
+    }
+    let primary_span = MultiSpan::from_spans(primary_spans);
+    // Note: An empty match isn't the same as an empty matrix for diagnostics purposes,
+    // since an empty matrix can occur when there are arms, if those arms all have guards.
+    let is_empty_match = arms.is_empty();
     let non_empty_enum = match scrut_ty.kind() {
         ty::Adt(def, _) => def.is_enum() && !def.variants.is_empty(),
         _ => false,
@@ -505,14 +1258,14 @@ fn non_exhaustive_match<'p, 'tcx>(
     if is_empty_match && !non_empty_enum {
         err = create_e0004(
             cx.tcx.sess,
-            sp,
+            primary_span,
             format!("non-exhaustive patterns: type `{}` is non-empty", scrut_ty),
         );
     } else {
-        let joined_patterns = joined_uncovered_patterns(&witnesses);
+        let joined_patterns = joined_uncovered_patterns(&witnesses, witnesses_truncated);
         err = create_e0004(
             cx.tcx.sess,
-            sp,
+            primary_span,
             format!("non-exhaustive patterns: {} not covered", joined_patterns),
         );
         err.span_label(sp, pattern_not_covered_label(&witnesses, &joined_patterns));
@@ -524,15 +1277,23 @@ fn non_exhaustive_match<'p, 'tcx>(
     };
 
     adt_defined_here(cx, &mut err, scrut_ty, &witnesses);
-    err.help(
-        "ensure that all possible cases are being handled, \
-              possibly by adding wildcards or more match arms",
-    );
+    err.help(&non_exhaustive_match_help(cx.tcx, scrut_ty, &witnesses));
     err.note(&format!(
         "the matched value is of type `{}`{}",
         scrut_ty,
         if is_variant_list_non_exhaustive { ", which is marked as non-exhaustive" } else { "" }
     ));
+    if peeled_refs {
+        err.note(PEELED_REFS_NOTE);
+    }
+    if let Some(note) = witnesses.iter().take(3).find_map(slice_witness_note) {
+        err.note(&note);
+    }
+    if let Some(ExpnData { kind: ExpnKind::Macro(kind, name), .. }) = outermost_macro {
+        if match_span.from_expansion() {
+            err.note(&format!("this `match` is generated by the {} `{}`", kind.descr(), name));
+        }
+    }
     if (scrut_ty == cx.tcx.types.usize || scrut_ty == cx.tcx.types.isize)
         && !is_empty_match
         && witnesses.len() == 1
@@ -552,14 +1313,326 @@ fn non_exhaustive_match<'p, 'tcx>(
         }
     }
     if let ty::Ref(_, sub_ty, _) = scrut_ty.kind() {
-        if cx.tcx.is_ty_uninhabited_from(cx.module, sub_ty, cx.param_env) {
+        if cx.is_uninhabited_from_module(sub_ty) {
             err.note("references are always considered inhabited");
         }
+        if !is_empty_match
+            && sub_ty.is_adt()
+            && witnesses.iter().all(|witness| matches!(*witness.kind, PatKind::Deref { .. }))
+        {
+            suggest_matching_referent(&mut err, scrut, arms);
+        }
+    }
+    if exhaustive_with_gate {
+        err.note("this match would be exhaustive with `#![feature(exhaustive_patterns)]`");
+    }
+    // Only some of the missing arms are known, and adding those wouldn't fix the error.
+    if !witnesses_truncated {
+        suggest_missing_arms(cx.tcx, &mut err, scrut.hir_id, match_span, arms, &witnesses);
+    }
+    cx.buffer(err);
+}
+
+/// The help for a non-exhaustive match. Values of integers and `char`s are too many to list, so
+/// point at wildcards and ranges there, and name the elements a tuple's missing patterns depend on.
+fn non_exhaustive_match_help<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    scrut_ty: Ty<'tcx>,
+    witnesses: &[super::Pat<'tcx>],
+) -> String {
+    if let Some(help) = missing_standard_case_help(tcx, scrut_ty, witnesses) {
+        return help;
+    }
+    match scrut_ty.kind() {
+        ty::Int(_) | ty::Uint(_) | ty::Char => {
+            return format!(
+                "ensure that all possible cases are being handled by adding a wildcard `_` arm \
+                    or range patterns that cover every `{}` value",
+                scrut_ty,
+            );
+        }
+        ty::Float(_) => {
+            return format!(
+                "ensure that all possible cases are being handled by adding a wildcard `_` arm, \
+                    since `{}` values cannot all be listed",
+                scrut_ty,
+            );
+        }
+        ty::Array(..) => {
+            return "ensure that all possible cases are being handled, \
+                possibly by adding wildcards or more array patterns"
+                .to_string();
+        }
+        ty::Tuple(..) => {
+            // An element is part of the problem if a missing pattern restricts it. Patterns like
+            // `()` or `(_, _)` match every value, even though they are not wildcards.
+            fn is_unrestricted(pat: &super::Pat<'_>) -> bool {
+                match &*pat.kind {
+                    PatKind::Leaf { subpatterns } => {
+                        subpatterns.iter().all(|field_pat| is_unrestricted(&field_pat.pattern))
+                    }
+                    PatKind::Deref { subpattern } => is_unrestricted(subpattern),
+                    _ => is_wildcard(pat),
+                }
+            }
+            let mut elements: Vec<_> = witnesses
+                .iter()
+                .flat_map(|witness| match &*witness.kind {
+                    PatKind::Leaf { subpatterns } => &subpatterns[..],
+                    _ => &[][..],
+                })
+                .filter(|field_pat| !is_unrestricted(&field_pat.pattern))
+                .map(|field_pat| field_pat.field.index())
+                .collect();
+            elements.sort_unstable();
+            elements.dedup();
+            let elements: Vec<_> = elements.iter().map(|i| format!("`.{}`", i)).collect();
+            let elements = match &elements[..] {
+                [] => None,
+                [element] => Some(format!("element {}", element)),
+                [head @ .., tail] => Some(format!("elements {} and {}", head.join(", "), tail)),
+            };
+            if let Some(elements) = elements {
+                return format!(
+                    "ensure that all possible cases are being handled for tuple {}, \
+                        possibly by adding wildcards or more match arms",
+                    elements,
+                );
+            }
+        }
+        _ => {}
     }
-    err.emit();
+    "ensure that all possible cases are being handled, \
+        possibly by adding wildcards or more match arms"
+        .to_string()
 }
 
-fn joined_uncovered_patterns(witnesses: &[super::Pat<'_>]) -> String {
+/// Matching on an `Option` or a `Result` and forgetting one of its two variants is by far the
+/// most common non-exhaustive match, so name the missing case directly.
+fn missing_standard_case_help<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    scrut_ty: Ty<'tcx>,
+    witnesses: &[super::Pat<'tcx>],
+) -> Option<String> {
+    let def = match scrut_ty.peel_refs().kind() {
+        ty::Adt(def, _) => def,
+        _ => return None,
+    };
+    if !tcx.is_diagnostic_item(sym::option_type, def.did)
+        && !tcx.is_diagnostic_item(sym::result_type, def.did)
+    {
+        return None;
+    }
+    let mut witness = match witnesses {
+        [witness] => witness,
+        _ => return None,
+    };
+    while let PatKind::Deref { subpattern } = &*witness.kind {
+        witness = subpattern;
+    }
+    match &*witness.kind {
+        // Only a variant that is missing entirely, like `Some(_)`, is a missing case. A witness
+        // like `Some(false)` is better explained by the witness itself.
+        PatKind::Variant { adt_def, variant_index, subpatterns, .. }
+            if subpatterns.iter().all(|field_pat| is_wildcard(&field_pat.pattern)) =>
+        {
+            Some(format!(
+                "the `{}` case is not handled, so add an arm for `{}`",
+                adt_def.variants[*variant_index].ident,
+                witness,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Hand the missing arms, written out, to tools like IDEs and rustfix that want to fill in the
+/// `match` for the user. The human-readable output already lists the witnesses, so nothing is
+/// shown there.
+fn suggest_missing_arms(
+    tcx: TyCtxt<'_>,
+    err: &mut DiagnosticBuilder<'_>,
+    hir_id: HirId,
+    match_span: Span,
+    arms: &[hir::Arm<'_>],
+    witnesses: &[super::Pat<'_>],
+) {
+    let source_map = tcx.sess.source_map();
+    let write_arms = |indent: usize, separator: &str| -> String {
+        witnesses
+            .iter()
+            .map(|witness| {
+                let pat = witness_to_source(tcx, hir_id, witness);
+                format!("{}\n{}{} => todo!()", separator, " ".repeat(indent), pat)
+            })
+            .collect()
+    };
+    let (span, missing_arms) = match arms.last() {
+        Some(last_arm) if !last_arm.span.from_expansion() => {
+            let indent = source_map.span_to_margin(last_arm.span).unwrap_or(0);
+            (last_arm.span.shrink_to_hi(), write_arms(indent, ","))
+        }
+        Some(_) => return,
+        // Without arms to line up with, the arms go right before the closing brace.
+        None => {
+            if match_span.from_expansion() {
+                return;
+            }
+            let close_brace = match_span.with_lo(match_span.hi() - BytePos(1));
+            if source_map.span_to_snippet(close_brace).map_or(true, |s| s != "}") {
+                return;
+            }
+            let indent = source_map.span_to_margin(match_span).unwrap_or(0);
+            let mut missing_arms = write_arms(indent + 4, "");
+            missing_arms.push_str(&format!(",\n{}", " ".repeat(indent)));
+            (close_brace.shrink_to_lo(), missing_arms)
+        }
+    };
+    // The arms are `todo!()`s, and a variant nested in a witness is written by name only, which
+    // may not be in scope.
+    err.tool_only_span_suggestion(
+        span,
+        "add the missing match arms",
+        missing_arms,
+        Applicability::HasPlaceholders,
+    );
+}
+
+/// Render a witness so that it can be written in the `match` at `hir_id`. Unlike the `Display`
+/// output, a top-level `enum` variant is qualified with the path to its `enum`.
+fn witness_to_source(tcx: TyCtxt<'_>, hir_id: HirId, witness: &super::Pat<'_>) -> String {
+    match &*witness.kind {
+        // The `Display` output starts with the variant's name.
+        PatKind::Variant { adt_def, .. } => {
+            format!("{}::{}", variant_suggestion_path(tcx, hir_id, adt_def).0, witness)
+        }
+        _ => witness.to_string(),
+    }
+}
+
+/// All the missing patterns of a match on `&Adt` are reference patterns. Suggest matching on the
+/// referenced value directly, which lets the missing arms be written without `&`.
+fn suggest_matching_referent(
+    err: &mut DiagnosticBuilder<'_>,
+    scrut: &hir::Expr<'_>,
+    arms: &[hir::Arm<'_>],
+) {
+    // Dereferencing the scrutinee would break arms that spell out the reference themselves.
+    let has_ref_arms = arms.iter().any(|arm| matches!(arm.pat.kind, hir::PatKind::Ref(..)));
+    if has_ref_arms || scrut.span.from_expansion() {
+        err.help("the matched value is a reference, so the missing arms need `&` patterns");
+    } else if let hir::ExprKind::AddrOf(hir::BorrowKind::Ref, _, inner) = scrut.kind {
+        err.span_suggestion_verbose(
+            scrut.span.until(inner.span),
+            "consider matching on the value itself instead of a reference to it",
+            String::new(),
+            Applicability::MaybeIncorrect,
+        );
+    } else if scrut.precedence().order() >= PREC_PREFIX {
+        err.span_suggestion_verbose(
+            scrut.span.shrink_to_lo(),
+            "consider dereferencing the matched value to match on it without `&` patterns",
+            "*".to_string(),
+            Applicability::MaybeIncorrect,
+        );
+    } else {
+        err.help("consider dereferencing the matched value or adding `&` to the missing arms");
+    }
+}
+
+/// Spell out which slices a witness like `&[_, _, ..]` stands for, with an example value when the
+/// elements have an obvious one. Slices without wildcards, like `&[]`, need no explanation.
+fn slice_witness_note(witness: &super::Pat<'_>) -> Option<String> {
+    let mut refs = String::new();
+    let mut pat = witness;
+    while let PatKind::Deref { subpattern } = &*pat.kind {
+        refs.push('&');
+        pat = subpattern;
+    }
+    let (elements, is_var_len): (Vec<_>, _) = match &*pat.kind {
+        PatKind::Slice { prefix, slice, suffix } => {
+            (prefix.iter().chain(suffix).collect(), slice.is_some())
+        }
+        _ => return None,
+    };
+    if !is_var_len && !elements.iter().any(|element| is_wildcard(element)) {
+        return None;
+    }
+    let mut note = format!(
+        "`{}` stands for a slice of length {}{}",
+        witness,
+        elements.len(),
+        if is_var_len { " or more" } else { "" },
+    );
+    let example: Option<Vec<_>> = elements.iter().map(|element| example_value(element)).collect();
+    if let Some(example) = example {
+        note.push_str(&format!(", e.g. `{}[{}]`", refs, example.join(", ")));
+    }
+    Some(note)
+}
+
+/// A value matched by `pat` that can be shown as an example, if there is an obvious one.
+fn example_value(pat: &super::Pat<'_>) -> Option<String> {
+    if !is_wildcard(pat) {
+        // Only use patterns that are themselves values, e.g. `false` or `None`.
+        let pat = pat.to_string();
+        return if pat.contains('_') || pat.contains("..") { None } else { Some(pat) };
+    }
+    match pat.ty.kind() {
+        ty::Bool => Some("false".to_string()),
+        ty::Int(_) | ty::Uint(_) => Some("0".to_string()),
+        ty::Char => Some("'a'".to_string()),
+        _ => None,
+    }
+}
+
+const PEELED_REFS_NOTE: &str =
+    "patterns match through references, so the missing patterns are shown without their `&`s";
+
+/// Patterns match through references thanks to default binding modes, so spelling out all the
+/// `&`s of a witness like `&&mut &Some(_)` only gets in the way. If the matched value is behind
+/// several references, strip them from the witnesses. Also returns whether that happened.
+fn peel_witness_refs<'tcx>(
+    ty: Ty<'tcx>,
+    witnesses: Vec<super::Pat<'tcx>>,
+) -> (Vec<super::Pat<'tcx>>, bool) {
+    let mut depth = 0;
+    let mut inner_ty = ty;
+    while let ty::Ref(_, sub_ty, _) = inner_ty.kind() {
+        depth += 1;
+        inner_ty = sub_ty;
+    }
+    let is_peelable = |witness: &super::Pat<'tcx>| {
+        let mut witness = witness;
+        for _ in 0..depth {
+            match &*witness.kind {
+                PatKind::Deref { subpattern } => witness = subpattern,
+                _ => return false,
+            }
+        }
+        true
+    };
+    if depth < 2 || !witnesses.iter().all(is_peelable) {
+        return (witnesses, false);
+    }
+    let peeled = witnesses
+        .into_iter()
+        .map(|mut witness| {
+            for _ in 0..depth {
+                witness = match *witness.kind {
+                    PatKind::Deref { subpattern } => subpattern,
+                    _ => bug!(),
+                };
+            }
+            witness
+        })
+        .collect();
+    (peeled, true)
+}
+
+/// List the first few witnesses. If `truncated`, there are more witnesses than were kept, so the
+/// count of the ones that aren't listed is only a lower bound.
+fn joined_uncovered_patterns(witnesses: &[super::Pat<'_>], truncated: bool) -> String {
     const LIMIT: usize = 3;
     match witnesses {
         [] => bug!(),
@@ -571,15 +1644,27 @@ fn joined_uncovered_patterns(witnesses: &[super::Pat<'_>]) -> String {
         _ => {
             let (head, tail) = witnesses.split_at(LIMIT);
             let head: Vec<_> = head.iter().map(<_>::to_string).collect();
-            format!("`{}` and {} more", head.join("`, `"), tail.len())
+            let at_least = if truncated { "at least " } else { "" };
+            format!("`{}` and {}{} more", head.join("`, `"), at_least, tail.len())
         }
     }
 }
 
 fn pattern_not_covered_label(witnesses: &[super::Pat<'_>], joined_patterns: &str) -> String {
+    // A lone `_` doesn't say which values are missing, so name the type whose values can't be
+    // listed, like a float, an integer without a fixed maximum value or a `#[non_exhaustive]` enum.
+    if let [witness] = witnesses {
+        if is_wildcard(witness) {
+            return format!("pattern `_` of type `{}` not covered", witness.ty);
+        }
+    }
     format!("pattern{} {} not covered", rustc_errors::pluralize!(witnesses.len()), joined_patterns)
 }
 
+/// Maximum number of uncovered variants we point at in the definition of an `enum`, unless the
+/// user asked for verbose output.
+const MAX_VARIANT_LABELS: usize = 3;
+
 /// Point at the definition of non-covered `enum` variants.
 fn adt_defined_here(
     cx: &MatchCheckCtxt<'_, '_>,
@@ -593,16 +1678,21 @@ fn adt_defined_here(
             err.span_label(sp, format!("`{}` defined here", ty));
         }
 
-        if witnesses.len() < 4 {
-            for sp in maybe_point_at_variant(ty, &witnesses) {
-                err.span_label(sp, "not covered");
+        if witnesses.len() <= MAX_VARIANT_LABELS || cx.tcx.sess.verbose() {
+            let witnesses: Vec<_> = witnesses.iter().collect();
+            for (sp, label) in maybe_point_at_variant(cx.tcx, ty, &witnesses) {
+                err.span_label(sp, label);
             }
         }
     }
 }
 
-fn maybe_point_at_variant(ty: Ty<'_>, patterns: &[super::Pat<'_>]) -> Vec<Span> {
-    let mut covered = vec![];
+fn maybe_point_at_variant(
+    tcx: TyCtxt<'_>,
+    ty: Ty<'_>,
+    patterns: &[&super::Pat<'_>],
+) -> Vec<(Span, &'static str)> {
+    let mut covered: Vec<(Span, &'static str)> = vec![];
     if let ty::Adt(def, _) = ty.kind() {
         // Don't point at variants that have already been covered due to other patterns to avoid
         // visual clutter.
@@ -610,31 +1700,43 @@ fn maybe_point_at_variant(ty: Ty<'_>, patterns: &[super::Pat<'_>]) -> Vec<Span>
             use PatKind::{AscribeUserType, Deref, Leaf, Or, Variant};
             match &*pattern.kind {
                 AscribeUserType { subpattern, .. } | Deref { subpattern } => {
-                    covered.extend(maybe_point_at_variant(ty, slice::from_ref(&subpattern)));
+                    covered.extend(maybe_point_at_variant(tcx, ty, &[subpattern]));
                 }
                 Variant { adt_def, variant_index, subpatterns, .. } if adt_def.did == def.did => {
-                    let sp = def.variants[*variant_index].ident.span;
-                    if covered.contains(&sp) {
+                    let variant = &def.variants[*variant_index];
+                    let sp = variant.ident.span;
+                    if covered.iter().any(|&(covered_sp, _)| covered_sp == sp) {
                         continue;
                     }
-                    covered.push(sp);
+                    covered.push((sp, "not covered"));
 
-                    let pats = subpatterns
-                        .iter()
-                        .map(|field_pattern| field_pattern.pattern.clone())
-                        .collect::<Box<[_]>>();
-                    covered.extend(maybe_point_at_variant(ty, &pats));
+                    // A witness with a non-wildcard field means the variant is partially handled.
+                    // Point at the fields that are missing cases, unless the variant has a single
+                    // field, in which case pointing at the variant is already unambiguous.
+                    if variant.fields.len() > 1 {
+                        for field_pattern in subpatterns {
+                            if is_wildcard(&field_pattern.pattern) {
+                                continue;
+                            }
+                            let field = &variant.fields[field_pattern.field.index()];
+                            if let Some(field_sp) = tcx.hir().span_if_local(field.did) {
+                                covered.push((field_sp, "not fully covered"));
+                            }
+                        }
+                    }
+
+                    let pats: Vec<_> =
+                        subpatterns.iter().map(|field_pattern| &field_pattern.pattern).collect();
+                    covered.extend(maybe_point_at_variant(tcx, ty, &pats));
                 }
                 Leaf { subpatterns } => {
-                    let pats = subpatterns
-                        .iter()
-                        .map(|field_pattern| field_pattern.pattern.clone())
-                        .collect::<Box<[_]>>();
-                    covered.extend(maybe_point_at_variant(ty, &pats));
+                    let pats: Vec<_> =
+                        subpatterns.iter().map(|field_pattern| &field_pattern.pattern).collect();
+                    covered.extend(maybe_point_at_variant(tcx, ty, &pats));
                 }
                 Or { pats } => {
-                    let pats = pats.iter().cloned().collect::<Box<[_]>>();
-                    covered.extend(maybe_point_at_variant(ty, &pats));
+                    let pats: Vec<_> = pats.iter().collect();
+                    covered.extend(maybe_point_at_variant(tcx, ty, &pats));
                 }
                 _ => {}
             }
@@ -645,7 +1747,7 @@ fn maybe_point_at_variant(ty: Ty<'_>, patterns: &[super::Pat<'_>]) -> Vec<Span>
 
 /// Check if a by-value binding is by-value. That is, check if the binding's type is not `Copy`.
 fn is_binding_by_move(cx: &MatchVisitor<'_, '_>, hir_id: HirId, span: Span) -> bool {
-    !cx.typeck_results.node_type(hir_id).is_copy_modulo_regions(cx.tcx.at(span), cx.param_env)
+    !cx.typeck_results.node_type(hir_id).is_copy_modulo_regions(cx.tcx.at(span), cx.cx.param_env)
 }
 
 /// Check that there are no borrow or move conflicts in `binding @ subpat` patterns.
@@ -660,8 +1762,8 @@ fn is_binding_by_move(cx: &MatchVisitor<'_, '_>, hir_id: HirId, span: Span) -> b
 /// This analysis is *not* subsumed by NLL.
 fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_>) {
     // Extract `sub` in `binding @ sub`.
-    let (name, sub) = match &pat.kind {
-        hir::PatKind::Binding(.., name, Some(sub)) => (*name, sub),
+    let (annotation, name, sub) = match &pat.kind {
+        hir::PatKind::Binding(annotation, _, name, Some(sub)) => (*annotation, *name, sub),
         _ => return,
     };
     let binding_span = pat.span.with_hi(name.span.hi());
@@ -674,10 +1776,19 @@ fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_
         Some(ty::BindByValue(_)) if is_binding_by_move(cx, pat.hir_id, pat.span) => {
             // We have `x @ pat` where `x` is by-move. Reject all borrows in `pat`.
             let mut conflicts_ref = Vec::new();
+            // Whether `ref x @ sub` would be accepted, i.e. `sub` only has shared borrows
+            // and copies.
+            let mut can_suggest_ref = annotation == hir::BindingAnnotation::Unannotated;
             sub.each_binding(|_, hir_id, span, _| {
                 match typeck_results.extract_binding_mode(sess, hir_id, span) {
+                    Some(ty::BindByValue(_)) if is_binding_by_move(cx, hir_id, span) => {
+                        can_suggest_ref = false;
+                    }
                     Some(ty::BindByValue(_)) | None => {}
-                    Some(ty::BindByReference(_)) => conflicts_ref.push(span),
+                    Some(ty::BindByReference(m)) => {
+                        can_suggest_ref &= m == Mutability::Not;
+                        conflicts_ref.push(span);
+                    }
                 }
             });
             if !conflicts_ref.is_empty() {
@@ -686,11 +1797,27 @@ fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_
                     name,
                     typeck_results.node_type(pat.hir_id),
                 );
-                sess.struct_span_err(pat.span, "borrow of moved value")
-                    .span_label(binding_span, format!("value moved into `{}` here", name))
+                let mut err = sess.struct_span_err(pat.span, "borrow of moved value");
+                err.span_label(binding_span, format!("value moved into `{}` here", name))
                     .span_label(binding_span, occurs_because)
-                    .span_labels(conflicts_ref, "value borrowed here after move")
-                    .emit();
+                    .span_labels(conflicts_ref, "value borrowed here after move");
+                if can_suggest_ref {
+                    err.span_suggestion_verbose(
+                        pat.span.shrink_to_lo(),
+                        &format!("borrow the value instead of moving it into `{}`", name),
+                        "ref ".to_string(),
+                        Applicability::MachineApplicable,
+                    );
+                }
+                if !pat.span.from_expansion() && !sub.span.from_expansion() {
+                    err.span_suggestion_verbose(
+                        pat.span.with_hi(sub.span.lo()),
+                        &format!("if `{}` is not needed, bind only the inner values", name),
+                        String::new(),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+                cx.cx.buffer(err);
             }
             return;
         }
@@ -703,7 +1830,10 @@ fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_
     let mut conflicts_move = Vec::new();
     let mut conflicts_mut_mut = Vec::new();
     let mut conflicts_mut_ref = Vec::new();
-    sub.each_binding(|_, hir_id, span, name| {
+    // Whether all the by-move bindings can be made by-ref by simply prepending `ref`. Under a
+    // `ref mut` binding that would only trade this error for a mutability conflict.
+    let mut can_suggest_ref = mut_outer == Mutability::Not;
+    sub.each_binding(|annotation, hir_id, span, name| {
         match typeck_results.extract_binding_mode(sess, hir_id, span) {
             Some(ty::BindByReference(mut_inner)) => match (mut_outer, mut_inner) {
                 (Mutability::Not, Mutability::Not) => {} // Both sides are `ref`.
@@ -711,6 +1841,8 @@ fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_
                 _ => conflicts_mut_ref.push((span, name)), // `ref` + `ref mut` in either direction.
             },
             Some(ty::BindByValue(_)) if is_binding_by_move(cx, hir_id, span) => {
+                // Turning `mut x` into `ref mut x` would only trade this error for another one.
+                can_suggest_ref &= annotation == hir::BindingAnnotation::Unannotated;
                 conflicts_move.push((span, name)) // `ref mut?` + by-move conflict.
             }
             Some(ty::BindByValue(_)) | None => {} // `ref mut?` + by-copy is fine.
@@ -732,7 +1864,7 @@ fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_
         for (span, name) in conflicts_move {
             err.span_label(span, format!("also moved into `{}` here", name));
         }
-        err.emit();
+        cx.cx.buffer(err);
     } else if !conflicts_mut_ref.is_empty() {
         // Report mutability conflicts for e.g. `ref x @ Some(ref mut y)` or the converse.
         let (primary, also) = match mut_outer {
@@ -749,16 +1881,31 @@ fn check_borrow_conflicts_in_at_patterns(cx: &MatchVisitor<'_, '_>, pat: &Pat<'_
         for (span, name) in conflicts_move {
             err.span_label(span, format!("also moved into `{}` here", name));
         }
-        err.emit();
+        cx.cx.buffer(err);
     } else if !conflicts_move.is_empty() {
         // Report by-ref and by-move conflicts, e.g. `ref x @ y`.
         let mut err =
             sess.struct_span_err(pat.span, "cannot move out of value because it is borrowed");
         err.span_label(binding_span, format!("value borrowed, by `{}`, here", name));
-        for (span, name) in conflicts_move {
+        for &(span, name) in &conflicts_move {
             err.span_label(span, format!("value moved into `{}` here", name));
         }
-        err.emit();
+        if can_suggest_ref {
+            let msg = if conflicts_move.len() == 1 {
+                "borrow the value instead of moving it"
+            } else {
+                "borrow the values instead of moving them"
+            };
+            err.multipart_suggestion_verbose(
+                msg,
+                conflicts_move
+                    .iter()
+                    .map(|&(span, _)| (span.shrink_to_lo(), "ref ".to_string()))
+                    .collect(),
+                Applicability::MachineApplicable,
+            );
+        }
+        cx.cx.buffer(err);
     }
 }
 
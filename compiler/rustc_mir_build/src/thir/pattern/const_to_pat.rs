@@ -1,3 +1,4 @@
+use rustc_errors::{Diagnostic, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_index::vec::Idx;
 use rustc_infer::infer::{InferCtxt, TyCtxtInferExt};
@@ -11,7 +12,7 @@
 use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt;
 use rustc_trait_selection::traits::{self, ObligationCause, PredicateObligation};
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
 use super::PatCtxt;
 
@@ -27,10 +28,14 @@ pub(super) fn const_to_pat(
         span: Span,
         mir_structural_match_violation: bool,
     ) -> Pat<'tcx> {
-        let pat = self.tcx.infer_ctxt().enter(|infcx| {
+        let (pat, diagnostics) = self.tcx.infer_ctxt().enter(|infcx| {
             let mut convert = ConstToPat::new(self, id, span, infcx);
-            convert.to_pat(cv, mir_structural_match_violation)
+            let pat = convert.to_pat(cv, mir_structural_match_violation);
+            (pat, convert.diagnostics.into_inner())
         });
+        for diag in diagnostics {
+            self.emit_diagnostic(diag);
+        }
 
         debug!(?pat);
         pat
@@ -61,6 +66,10 @@ struct ConstToPat<'a, 'tcx> {
 
     include_lint_checks: bool,
 
+    /// The diagnostics reported while converting the constant, which `PatCtxt::const_to_pat`
+    /// then hands on.
+    diagnostics: RefCell<Vec<Diagnostic>>,
+
     treat_byte_string_as_slice: bool,
 }
 
@@ -96,6 +105,7 @@ fn new(
             infcx,
             param_env: pat_ctxt.param_env,
             include_lint_checks: pat_ctxt.include_lint_checks,
+            diagnostics: Default::default(),
             saw_const_match_error: Cell::new(false),
             saw_const_match_lint: Cell::new(false),
             behind_reference: Cell::new(false),
@@ -110,6 +120,10 @@ fn tcx(&self) -> TyCtxt<'tcx> {
         self.infcx.tcx
     }
 
+    fn emit(&self, err: DiagnosticBuilder<'_>) {
+        err.buffer(&mut self.diagnostics.borrow_mut());
+    }
+
     fn adt_derive_msg(&self, adt_def: &AdtDef) -> String {
         let path = self.tcx().def_path_str(adt_def.did);
         format!(
@@ -196,7 +210,7 @@ fn to_pat(
                         lint::builtin::INDIRECT_STRUCTURAL_MATCH,
                         self.id,
                         self.span,
-                        |lint| lint.build(&msg).emit(),
+                        |lint| self.emit(lint.build(&msg)),
                     );
                 } else {
                     debug!(
@@ -274,7 +288,9 @@ fn recur(
                         lint::builtin::ILLEGAL_FLOATING_POINT_LITERAL_PATTERN,
                         id,
                         span,
-                        |lint| lint.build("floating-point types cannot be used in patterns").emit(),
+                        |lint| {
+                            self.emit(lint.build("floating-point types cannot be used in patterns"))
+                        },
                     );
                 }
                 PatKind::Constant { value: cv }
@@ -284,7 +300,7 @@ fn recur(
                 self.saw_const_match_error.set(true);
                 let msg = "cannot use unions in constant patterns";
                 if self.include_lint_checks {
-                    tcx.sess.span_err(span, msg);
+                    self.emit(tcx.sess.struct_span_err(span, msg));
                 } else {
                     tcx.sess.delay_span_bug(span, msg)
                 }
@@ -301,7 +317,7 @@ fn recur(
                 let msg = self.search_for_structural_match_violation(cv.ty).unwrap();
                 self.saw_const_match_error.set(true);
                 if self.include_lint_checks {
-                    tcx.sess.span_err(self.span, &msg);
+                    self.emit(tcx.sess.struct_span_err(self.span, &msg));
                 } else {
                     tcx.sess.delay_span_bug(self.span, &msg)
                 }
@@ -331,7 +347,7 @@ fn recur(
                         lint::builtin::INDIRECT_STRUCTURAL_MATCH,
                         id,
                         span,
-                        |lint| lint.build(&msg).emit(),
+                        |lint| self.emit(lint.build(&msg)),
                     );
                 }
                 // Since we are behind a reference, we can just bubble the error up so we get a
@@ -349,7 +365,7 @@ fn recur(
                 );
                 self.saw_const_match_error.set(true);
                 if self.include_lint_checks {
-                    tcx.sess.span_err(span, &msg);
+                    self.emit(tcx.sess.struct_span_err(span, &msg));
                 } else {
                     tcx.sess.delay_span_bug(span, &msg)
                 }
@@ -386,7 +402,7 @@ fn recur(
                     self.saw_const_match_error.set(true);
                     let msg = format!("`{}` cannot be used in patterns", cv.ty);
                     if self.include_lint_checks {
-                        tcx.sess.span_err(span, &msg);
+                        self.emit(tcx.sess.struct_span_err(span, &msg));
                     } else {
                         tcx.sess.delay_span_bug(span, &msg)
                     }
@@ -466,7 +482,7 @@ fn recur(
                                 lint::builtin::INDIRECT_STRUCTURAL_MATCH,
                                 self.id,
                                 self.span,
-                                |lint| lint.build(&msg).emit(),
+                                |lint| self.emit(lint.build(&msg)),
                             );
                         }
                         PatKind::Constant { value: cv }
@@ -475,7 +491,7 @@ fn recur(
                             self.saw_const_match_error.set(true);
                             let msg = self.adt_derive_msg(adt_def);
                             if self.include_lint_checks {
-                                tcx.sess.span_err(span, &msg);
+                                self.emit(tcx.sess.struct_span_err(span, &msg));
                             } else {
                                 tcx.sess.delay_span_bug(span, &msg)
                             }
@@ -492,7 +508,7 @@ fn recur(
                         // (except slices, which are handled in a separate arm above).
                         let msg = format!("cannot use unsized non-slice type `{}` in constant patterns", pointee_ty);
                         if self.include_lint_checks {
-                            tcx.sess.span_err(span, &msg);
+                            self.emit(tcx.sess.struct_span_err(span, &msg));
                         } else {
                             tcx.sess.delay_span_bug(span, &msg);
                         }
@@ -534,7 +550,7 @@ fn recur(
                         lint::builtin::POINTER_STRUCTURAL_MATCH,
                         id,
                         span,
-                        |lint| lint.build(&msg).emit(),
+                        |lint| self.emit(lint.build(&msg)),
                     );
                 }
                 PatKind::Constant { value: cv }
@@ -543,7 +559,7 @@ fn recur(
                 self.saw_const_match_error.set(true);
                 let msg = format!("`{}` cannot be used in patterns", cv.ty);
                 if self.include_lint_checks {
-                    tcx.sess.span_err(span, &msg);
+                    self.emit(tcx.sess.struct_span_err(span, &msg));
                 } else {
                     tcx.sess.delay_span_bug(span, &msg)
                 }
@@ -570,7 +586,7 @@ fn recur(
                 lint::builtin::NONTRIVIAL_STRUCTURAL_MATCH,
                 id,
                 span,
-                |lint| lint.build(&msg).emit(),
+                |lint| self.emit(lint.build(&msg)),
             );
         }
 
@@ -49,6 +49,7 @@
 use super::usefulness::{is_wildcard, MatchCheckCtxt, PatCtxt};
 
 use rustc_data_structures::captures::Captures;
+use rustc_index::bit_set::GrowableBitSet;
 use rustc_index::vec::Idx;
 
 use rustc_hir::{HirId, RangeEnd};
@@ -222,16 +223,17 @@ fn to_pat<'tcx>(&self, tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Pat<'tcx> {
         Pat { ty, span: DUMMY_SP, kind: Box::new(kind) }
     }
 
-    /// Lint on likely incorrect range patterns (#63987)
+    /// Lint on likely incorrect range patterns (#63987). Returns whether there was anything to
+    /// lint about.
     pub(super) fn lint_overlapping_range_endpoints<'a, 'tcx: 'a>(
         &self,
         pcx: PatCtxt<'_, '_, 'tcx>,
         ctors: impl Iterator<Item = (&'a Constructor<'tcx>, Span)>,
         column_count: usize,
         hir_id: HirId,
-    ) {
+    ) -> bool {
         if self.is_singleton() {
-            return;
+            return false;
         }
 
         if column_count != 1 {
@@ -245,7 +247,7 @@ pub(super) fn lint_overlapping_range_endpoints<'a, 'tcx: 'a>(
             //   _ => {}
             // }
             // ```
-            return;
+            return false;
         }
 
         let overlaps: Vec<_> = ctors
@@ -254,7 +256,8 @@ pub(super) fn lint_overlapping_range_endpoints<'a, 'tcx: 'a>(
             .map(|(range, span)| (self.intersection(&range).unwrap(), span))
             .collect();
 
-        if !overlaps.is_empty() {
+        let any_overlaps = !overlaps.is_empty();
+        if any_overlaps {
             pcx.cx.tcx.struct_span_lint_hir(
                 lint::builtin::OVERLAPPING_RANGE_ENDPOINTS,
                 hir_id,
@@ -272,10 +275,11 @@ pub(super) fn lint_overlapping_range_endpoints<'a, 'tcx: 'a>(
                     }
                     err.span_label(pcx.span, "... with this range");
                     err.note("you likely meant to write mutually exclusive ranges");
-                    err.emit();
+                    pcx.cx.buffer(err);
                 },
             );
         }
+        any_overlaps
     }
 
     /// See `Constructor::is_covered_by`
@@ -855,6 +859,9 @@ fn is_covered_by_any<'p>(
 pub(super) struct SplitWildcard<'tcx> {
     /// Constructors seen in the matrix.
     matrix_ctors: Vec<Constructor<'tcx>>,
+    /// The variants among `matrix_ctors`. Looking for each variant of an `enum` in `matrix_ctors`
+    /// would take quadratic time, which matters for generated `enum`s with thousands of variants.
+    matrix_variants: GrowableBitSet<VariantIdx>,
     /// All the constructors for this type
     all_ctors: SmallVec<[Constructor<'tcx>; 1]>,
 }
@@ -916,12 +923,12 @@ pub(super) fn new<'p>(pcx: PatCtxt<'_, 'p, 'tcx>) -> Self {
                 // exception is if the pattern is at the top level, because we want empty matches to be
                 // considered exhaustive.
                 let is_secretly_empty = def.variants.is_empty()
-                    && !cx.tcx.features().exhaustive_patterns
+                    && !cx.exhaustive_patterns
                     && !pcx.is_top_level;
 
                 if is_secretly_empty || is_declared_nonexhaustive {
                     smallvec![NonExhaustive]
-                } else if cx.tcx.features().exhaustive_patterns {
+                } else if cx.exhaustive_patterns {
                     // If `exhaustive_patterns` is enabled, we exclude variants known to be
                     // uninhabited.
                     def.variants
@@ -966,16 +973,27 @@ pub(super) fn new<'p>(pcx: PatCtxt<'_, 'p, 'tcx>) -> Self {
             // If `exhaustive_patterns` is disabled and our scrutinee is the never type, we cannot
             // expose its emptiness. The exception is if the pattern is at the top level, because we
             // want empty matches to be considered exhaustive.
-            ty::Never if !cx.tcx.features().exhaustive_patterns && !pcx.is_top_level => {
+            ty::Never if !cx.exhaustive_patterns && !pcx.is_top_level => {
                 smallvec![NonExhaustive]
             }
             ty::Never => smallvec![],
+            // Likewise, tuples and structs that visibly contain an empty type can be matched with
+            // an empty match.
+            ty::Adt(..) | ty::Tuple(..)
+                if !cx.exhaustive_patterns && pcx.is_top_level && cx.is_visibly_empty(pcx.ty) =>
+            {
+                smallvec![]
+            }
             _ if cx.is_uninhabited(pcx.ty) => smallvec![],
             ty::Adt(..) | ty::Tuple(..) | ty::Ref(..) => smallvec![Single],
             // This type is one for which we cannot list constructors, like `str` or `f64`.
             _ => smallvec![NonExhaustive],
         };
-        SplitWildcard { matrix_ctors: Vec::new(), all_ctors }
+        SplitWildcard {
+            matrix_ctors: Vec::new(),
+            matrix_variants: GrowableBitSet::new_empty(),
+            all_ctors,
+        }
     }
 
     /// Pass a set of constructors relative to which to split this one. Don't call twice, it won't
@@ -991,6 +1009,11 @@ pub(super) fn split<'a>(
         self.all_ctors =
             self.all_ctors.iter().flat_map(|ctor| ctor.split(pcx, ctors.clone())).collect();
         self.matrix_ctors = ctors.filter(|c| !c.is_wildcard()).cloned().collect();
+        for ctor in &self.matrix_ctors {
+            if let Variant(idx) = *ctor {
+                self.matrix_variants.insert(idx);
+            }
+        }
     }
 
     /// Whether there are any value constructors for this type that are not present in the matrix.
@@ -1003,7 +1026,10 @@ pub(super) fn iter_missing<'a, 'p>(
         &'a self,
         pcx: PatCtxt<'a, 'p, 'tcx>,
     ) -> impl Iterator<Item = &'a Constructor<'tcx>> + Captures<'p> {
-        self.all_ctors.iter().filter(move |ctor| !ctor.is_covered_by_any(pcx, &self.matrix_ctors))
+        self.all_ctors.iter().filter(move |ctor| match **ctor {
+            Variant(idx) => !self.matrix_variants.contains(idx),
+            _ => !ctor.is_covered_by_any(pcx, &self.matrix_ctors),
+        })
     }
 
     /// Return the set of constructors resulting from splitting the wildcard. As explained at the
@@ -1359,16 +1385,11 @@ fn replace_fields_indexed(
 
     /// Replaces contained fields with the given list of patterns. There must be `len()` patterns
     /// in `pats`.
-    pub(super) fn replace_fields(
-        &self,
-        cx: &MatchCheckCtxt<'p, 'tcx>,
-        pats: impl IntoIterator<Item = Pat<'tcx>>,
-    ) -> Self {
-        let pats: &[_] = cx.pattern_arena.alloc_from_iter(pats);
+    pub(super) fn replace_fields(&self, pats: impl IntoIterator<Item = &'p Pat<'tcx>>) -> Self {
+        let mut pats = pats.into_iter();
 
         match self {
             Fields::Filtered { fields, len } => {
-                let mut pats = pats.iter();
                 let mut fields = fields.clone();
                 for f in &mut fields {
                     if let FilteredField::Kept(p) = f {
@@ -1378,7 +1399,7 @@ pub(super) fn replace_fields(
                 }
                 Fields::Filtered { fields, len: *len }
             }
-            _ => Fields::Slice(pats),
+            _ => Fields::Vec(pats.collect()),
         }
     }
 
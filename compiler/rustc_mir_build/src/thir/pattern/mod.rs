@@ -5,11 +5,13 @@
 mod deconstruct_pat;
 mod usefulness;
 
-pub(crate) use self::check_match::check_match;
+pub(crate) use self::check_match::{check_match, match_arm_reachability, missing_match_arms};
+pub use self::check_match::{check_synthetic_match, SyntheticMatchReport};
 
 use crate::thir::util::UserAnnotatedTyHelpers;
 
-use rustc_errors::struct_span_err;
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_errors::{struct_span_err, Diagnostic, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_hir::def::{CtorOf, DefKind, Res};
 use rustc_hir::pat_util::EnumerateAndAdjustIterator;
@@ -24,6 +26,7 @@
 use rustc_middle::ty::{self, AdtDef, ConstKind, DefIdTree, Region, Ty, TyCtxt, UserType};
 use rustc_span::{Span, Symbol};
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
 
 #[derive(Clone, Debug)]
@@ -40,6 +43,9 @@
     crate typeck_results: &'a ty::TypeckResults<'tcx>,
     crate errors: Vec<PatternError>,
     include_lint_checks: bool,
+    /// Where to put the diagnostics instead of emitting them right away, see
+    /// `MatchCheckCtxt::diagnostics`.
+    diagnostics: Option<&'a RefCell<Vec<Diagnostic>>>,
 }
 
 crate fn pat_from_hir<'a, 'tcx>(
@@ -64,7 +70,14 @@ impl<'a, 'tcx> PatCtxt<'a, 'tcx> {
         param_env: ty::ParamEnv<'tcx>,
         typeck_results: &'a ty::TypeckResults<'tcx>,
     ) -> Self {
-        PatCtxt { tcx, param_env, typeck_results, errors: vec![], include_lint_checks: false }
+        PatCtxt {
+            tcx,
+            param_env,
+            typeck_results,
+            errors: vec![],
+            include_lint_checks: false,
+            diagnostics: None,
+        }
     }
 
     crate fn include_lint_checks(&mut self) -> &mut Self {
@@ -72,6 +85,28 @@ impl<'a, 'tcx> PatCtxt<'a, 'tcx> {
         self
     }
 
+    crate fn buffer_diagnostics(
+        &mut self,
+        diagnostics: &'a RefCell<Vec<Diagnostic>>,
+    ) -> &mut Self {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
+    fn emit(&self, err: DiagnosticBuilder<'_>) {
+        match self.diagnostics {
+            Some(diagnostics) => err.buffer(&mut diagnostics.borrow_mut()),
+            None => err.emit(),
+        }
+    }
+
+    fn emit_diagnostic(&self, diag: Diagnostic) {
+        match self.diagnostics {
+            Some(diagnostics) => diagnostics.borrow_mut().push(diag),
+            None => self.tcx.sess.diagnostic().emit_diagnostic(&diag),
+        }
+    }
+
     crate fn lower_pattern(&mut self, pat: &'tcx hir::Pat<'tcx>) -> Pat<'tcx> {
         // When implicit dereferences have been inserted in this pattern, the unadjusted lowered
         // pattern has the type that results *after* dereferencing. For example, in this code:
@@ -92,7 +127,7 @@ impl<'a, 'tcx> PatCtxt<'a, 'tcx> {
         // we wrap the unadjusted pattern in `PatKind::Deref` repeatedly, consuming the
         // adjustments in *reverse order* (last-in-first-out, so that the last `Deref` inserted
         // gets the least-dereferenced type).
-        let unadjusted_pat = self.lower_pattern_unadjusted(pat);
+        let unadjusted_pat = ensure_sufficient_stack(|| self.lower_pattern_unadjusted(pat));
         self.typeck_results.pat_adjustments().get(pat.hir_id).unwrap_or(&vec![]).iter().rev().fold(
             unadjusted_pat,
             |pat, ref_ty| {
@@ -135,13 +170,12 @@ fn lower_pattern_range(
             (RangeEnd::Excluded, Some(Ordering::Less)) => PatKind::Range(PatRange { lo, hi, end }),
             // `x..y` where `x >= y`. The range is empty => error.
             (RangeEnd::Excluded, _) => {
-                struct_span_err!(
+                self.emit(struct_span_err!(
                     self.tcx.sess,
                     span,
                     E0579,
                     "lower range bound must be less than upper"
-                )
-                .emit();
+                ));
                 PatKind::Wild
             }
             // `x..=y` where `x == y`.
@@ -166,7 +200,7 @@ fn lower_pattern_range(
                               to be less than or equal to the end of the range.",
                     );
                 }
-                err.emit();
+                self.emit(err);
                 PatKind::Wild
             }
         }
@@ -477,7 +511,8 @@ fn lower_path(&mut self, qpath: &hir::QPath<'_>, id: hir::HirId, span: Span) ->
             }
 
             Err(_) => {
-                self.tcx.sess.span_err(span, "could not evaluate constant pattern");
+                let msg = "could not evaluate constant pattern";
+                self.emit(self.tcx.sess.struct_span_err(span, msg));
                 return pat_from_kind(PatKind::Wild);
             }
         };
@@ -523,11 +558,13 @@ fn lower_path(&mut self, qpath: &hir::QPath<'_>, id: hir::HirId, span: Span) ->
             Err(ErrorHandled::TooGeneric) => {
                 // While `Reported | Linted` cases will have diagnostics emitted already
                 // it is not true for TooGeneric case, so we need to give user more information.
-                self.tcx.sess.span_err(span, "constant pattern depends on a generic parameter");
+                let msg = "constant pattern depends on a generic parameter";
+                self.emit(self.tcx.sess.struct_span_err(span, msg));
                 pat_from_kind(PatKind::Wild)
             }
             Err(_) => {
-                self.tcx.sess.span_err(span, "could not evaluate constant pattern");
+                let msg = "could not evaluate constant pattern";
+                self.emit(self.tcx.sess.struct_span_err(span, msg));
                 pat_from_kind(PatKind::Wild)
             }
         }
@@ -567,7 +604,9 @@ fn lower_lit(&mut self, expr: &'tcx hir::Expr<'tcx>) -> PatKind<'tcx> {
                 LitToConstInput { lit: &lit.node, ty: self.typeck_results.expr_ty(expr), neg };
             match self.tcx.at(expr.span).lit_to_const(lit_input) {
                 Ok(val) => *self.const_to_pat(val, expr.hir_id, lit.span, false).kind,
-                Err(LitToConstError::Reported) => PatKind::Wild,
+                Err(LitToConstError::Reported) => {
+                    PatKind::Wild
+                }
                 Err(LitToConstError::TypeError) => bug!("lower_lit: had type error"),
             }
         }
@@ -287,19 +287,25 @@
 use super::{PatternFoldable, PatternFolder};
 
 use rustc_data_structures::captures::Captures;
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
 
 use rustc_arena::TypedArena;
+use rustc_errors::{Diagnostic, DiagnosticBuilder};
 use rustc_hir::def_id::DefId;
-use rustc_hir::HirId;
-use rustc_middle::thir::{Pat, PatKind};
+use rustc_hir::{HirId, RangeEnd};
+use rustc_middle::thir::{FieldPat, Pat, PatKind, PatRange};
 use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::MatchCheckInfo;
 use rustc_span::Span;
 
 use smallvec::{smallvec, SmallVec};
+use std::cell::RefCell;
 use std::fmt;
 use std::iter::{FromIterator, IntoIterator};
 use std::lazy::OnceCell;
+use std::mem;
+use std::time::Instant;
 
 crate struct MatchCheckCtxt<'a, 'tcx> {
     crate tcx: TyCtxt<'tcx>,
@@ -311,14 +317,76 @@
     crate module: DefId,
     crate param_env: ty::ParamEnv<'tcx>,
     crate pattern_arena: &'a TypedArena<Pat<'tcx>>,
+    /// Whether to reason about uninhabited types, as `#![feature(exhaustive_patterns)]` does.
+    /// This follows the feature gate, except when checking what enabling it would change.
+    crate exhaustive_patterns: bool,
+    /// Uninhabitedness is asked about the same types over and over, e.g. for every level of nested
+    /// `Result`s, and each answer walks the whole type. Shared by all the matches of a body.
+    crate uninhabited_cache: &'a RefCell<FxHashMap<(DefId, Ty<'tcx>), bool>>,
+    /// The results for the matches of the body seen so far, so that identical matches, like the
+    /// ones a macro expands to again and again, are only checked once.
+    crate report_cache: &'a RefCell<FxHashMap<ReportKey<'tcx>, CachedReport<'tcx>>>,
+    /// Where to write the steps of the algorithm if `-Z dump-match-check` asks for them. They are
+    /// written to a file once the whole body has been checked.
+    crate dump: Option<&'a RefCell<String>>,
+    /// The diagnostics reported for the body so far. They are emitted together, in the order of
+    /// their spans, once the whole body has been checked.
+    crate diagnostics: &'a RefCell<Vec<Diagnostic>>,
 }
 
 impl<'a, 'tcx> MatchCheckCtxt<'a, 'tcx> {
+    /// Holds on to a diagnostic until the whole body has been checked, see `diagnostics`.
+    crate fn buffer(&self, diag: DiagnosticBuilder<'_>) {
+        diag.buffer(&mut self.diagnostics.borrow_mut());
+    }
+
+    /// Adds a line to the `-Z dump-match-check` output of the body, if it was asked for.
+    fn dump_line(&self, line: impl FnOnce() -> String) {
+        if let Some(dump) = self.dump {
+            let mut dump = dump.borrow_mut();
+            dump.push_str(&line());
+            dump.push('\n');
+        }
+    }
+
     pub(super) fn is_uninhabited(&self, ty: Ty<'tcx>) -> bool {
-        if self.tcx.features().exhaustive_patterns {
-            self.tcx.is_ty_uninhabited_from(self.module, ty, self.param_env)
-        } else {
-            false
+        self.exhaustive_patterns && self.is_uninhabited_from_module(ty)
+    }
+
+    /// Whether the type is uninhabited as seen from the module of the match, regardless of
+    /// `exhaustive_patterns`.
+    pub(super) fn is_uninhabited_from_module(&self, ty: Ty<'tcx>) -> bool {
+        let key = (self.module, ty);
+        if let Some(&is_uninhabited) = self.uninhabited_cache.borrow().get(&key) {
+            return is_uninhabited;
+        }
+        let is_uninhabited = self.tcx.is_ty_uninhabited_from(self.module, ty, self.param_env);
+        self.uninhabited_cache.borrow_mut().insert(key, is_uninhabited);
+        is_uninhabited
+    }
+
+    /// Returns whether the given type is visibly empty even without `exhaustive_patterns`: the never
+    /// type, an empty enum, or a tuple or struct with a field of such a type that can be seen from
+    /// the current module.
+    pub(super) fn is_visibly_empty(&self, ty: Ty<'tcx>) -> bool {
+        match ty.kind() {
+            ty::Never => true,
+            ty::Adt(def, _) if def.is_enum() => {
+                def.variants.is_empty() && !self.is_foreign_non_exhaustive_enum(ty)
+            }
+            ty::Adt(def, substs) if def.is_struct() => {
+                let variant = def.non_enum_variant();
+                // The fields of a `#[non_exhaustive]` struct from another crate may change.
+                if variant.is_field_list_non_exhaustive() && !def.did.is_local() {
+                    return false;
+                }
+                variant.fields.iter().any(|field| {
+                    field.vis.is_accessible_from(self.module, self.tcx)
+                        && self.is_visibly_empty(field.ty(self.tcx, substs))
+                })
+            }
+            ty::Tuple(..) => ty.tuple_fields().any(|field_ty| self.is_visibly_empty(field_ty)),
+            _ => false,
         }
     }
 
@@ -409,6 +477,11 @@ fn expand<'p, 'tcx>(pat: &'p Pat<'tcx>, vec: &mut Vec<&'p Pat<'tcx>>) {
 
 /// A row of a matrix. Rows of len 1 are very common, which is why `SmallVec[_; 2]`
 /// works well.
+///
+/// The row holds references into the pattern arena, and specialization compares the cached
+/// `Constructor` of its head, which is already a compact tag: a variant index, an integer range or
+/// a slice length. A separate compact form of the whole row wouldn't save any work, since
+/// specializing needs the sub-patterns of the head to build the next row.
 #[derive(Clone)]
 struct PatStack<'p, 'tcx> {
     pats: SmallVec<[&'p Pat<'tcx>; 2]>,
@@ -857,6 +930,11 @@ fn unsplit_or_pat(mut self, alt_id: usize, alt_count: usize, pat: &'p Pat<'tcx>)
     }
 }
 
+/// How many witnesses we keep around for a sub-problem. Only a handful are ever displayed, but the
+/// arm suggestions and `generalize_witnesses` want all of them, so this only kicks in for the
+/// huge numbers of witnesses that wide wildcard gaps in generated matches can produce.
+const WITNESS_LIMIT: usize = 256;
+
 /// This carries the results of computing usefulness, as described at the top of the file. When
 /// checking usefulness of a match branch, we use the `NoWitnesses` variant, which also keeps track
 /// of potential unreachable sub-patterns (in the presence of or-patterns). When checking
@@ -873,7 +951,7 @@ enum Usefulness<'p, 'tcx> {
     NoWitnesses(SubPatSet<'p, 'tcx>),
     /// Carries a list of witnesses of non-exhaustiveness. If empty, indicates that the whole
     /// pattern is unreachable.
-    WithWitnesses(Vec<Witness<'tcx>>),
+    WithWitnesses(Vec<Witness<'p, 'tcx>>),
 }
 
 impl<'p, 'tcx> Usefulness<'p, 'tcx> {
@@ -895,12 +973,27 @@ fn extend(&mut self, other: Self) {
         match (&mut *self, other) {
             (WithWitnesses(_), WithWitnesses(o)) if o.is_empty() => {}
             (WithWitnesses(s), WithWitnesses(o)) if s.is_empty() => *self = WithWitnesses(o),
-            (WithWitnesses(s), WithWitnesses(o)) => s.extend(o),
+            // Keep one more than the limit so that `truncate_witnesses` knows some were dropped.
+            (WithWitnesses(s), WithWitnesses(o)) => {
+                let room = (WITNESS_LIMIT + 1).saturating_sub(s.len());
+                s.extend(o.into_iter().take(room))
+            }
             (NoWitnesses(s), NoWitnesses(o)) => s.union(o),
             _ => unreachable!(),
         }
     }
 
+    /// Drop the witnesses past `WITNESS_LIMIT`. Returns whether there were any.
+    fn truncate_witnesses(&mut self) -> bool {
+        match self {
+            WithWitnesses(witnesses) if witnesses.len() > WITNESS_LIMIT => {
+                witnesses.truncate(WITNESS_LIMIT);
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// When trying several branches and each returns a `Usefulness`, we need to combine the
     /// results together.
     fn merge(pref: WitnessPreference, usefulnesses: impl Iterator<Item = Self>) -> Self {
@@ -946,21 +1039,23 @@ fn apply_constructor(
                     // constructor, that matches everything that can be built with
                     // it. For example, if `ctor` is a `Constructor::Variant` for
                     // `Option::Some`, we get the pattern `Some(_)`.
-                    let new_patterns: Vec<_> = split_wildcard
+                    let new_patterns: Vec<&'p Pat<'tcx>> = split_wildcard
                         .iter_missing(pcx)
                         .map(|missing_ctor| {
-                            Fields::wildcards(pcx, missing_ctor).apply(pcx, missing_ctor)
+                            let pat = Fields::wildcards(pcx, missing_ctor).apply(pcx, missing_ctor);
+                            &*pcx.cx.pattern_arena.alloc(pat)
                         })
                         .collect();
                     witnesses
                         .into_iter()
                         .flat_map(|witness| {
-                            new_patterns.iter().map(move |pat| {
+                            new_patterns.iter().map(move |&pat| {
                                 let mut witness = witness.clone();
-                                witness.0.push(pat.clone());
+                                witness.0.push(pat);
                                 witness
                             })
                         })
+                        .take(WITNESS_LIMIT + 1)
                         .collect()
                 } else {
                     witnesses
@@ -975,7 +1070,7 @@ fn apply_constructor(
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum WitnessPreference {
     ConstructWitness,
     LeaveOutWitness,
@@ -1014,14 +1109,17 @@ enum WitnessPreference {
 ///     `Witness(vec![Pair(Some(_), true)])`
 ///
 /// The final `Pair(Some(_), true)` is then the resulting witness.
+///
+/// The patterns live in the arena of the `MatchCheckCtxt`, so that witnesses, which get copied
+/// for each missing constructor and for each cached result, are cheap to copy.
 #[derive(Clone, Debug)]
-crate struct Witness<'tcx>(Vec<Pat<'tcx>>);
+crate struct Witness<'p, 'tcx>(Vec<&'p Pat<'tcx>>);
 
-impl<'tcx> Witness<'tcx> {
+impl<'p, 'tcx> Witness<'p, 'tcx> {
     /// Asserts that the witness contains a single pattern, and returns it.
     fn single_pattern(self) -> Pat<'tcx> {
         assert_eq!(self.0.len(), 1);
-        self.0.into_iter().next().unwrap()
+        self.0[0].clone()
     }
 
     /// Constructs a partial witness for a pattern given a list of
@@ -1037,7 +1135,7 @@ fn single_pattern(self) -> Pat<'tcx> {
     ///
     /// left_ty: struct X { a: (bool, &'static str), b: usize}
     /// pats: [(false, "foo"), 42]  => X { a: (false, "foo"), b: 42 }
-    fn apply_constructor<'p>(
+    fn apply_constructor(
         mut self,
         pcx: PatCtxt<'_, 'p, 'tcx>,
         ctor: &Constructor<'tcx>,
@@ -1047,10 +1145,10 @@ fn apply_constructor<'p>(
             let len = self.0.len();
             let arity = ctor_wild_subpatterns.len();
             let pats = self.0.drain((len - arity)..).rev();
-            ctor_wild_subpatterns.replace_fields(pcx.cx, pats).apply(pcx, ctor)
+            ctor_wild_subpatterns.replace_fields(pats).apply(pcx, ctor)
         };
 
-        self.0.push(pat);
+        self.0.push(pcx.cx.pattern_arena.alloc(pat));
 
         self
     }
@@ -1080,7 +1178,7 @@ fn apply_constructor<'p>(
 /// relied on for soundness.
 #[instrument(
     level = "debug",
-    skip(cx, matrix, witness_preference, hir_id, is_under_guard, is_top_level)
+    skip(cx, matrix, witness_preference, hir_id, is_under_guard, is_top_level, state)
 )]
 fn is_useful<'p, 'tcx>(
     cx: &MatchCheckCtxt<'p, 'tcx>,
@@ -1090,6 +1188,7 @@ fn is_useful<'p, 'tcx>(
     hir_id: HirId,
     is_under_guard: bool,
     is_top_level: bool,
+    state: &mut UsefulnessState<'p, 'tcx>,
 ) -> Usefulness<'p, 'tcx> {
     debug!("matrix,v={:?}{:?}", matrix, v);
     let Matrix { patterns: rows, .. } = matrix;
@@ -1111,12 +1210,47 @@ fn is_useful<'p, 'tcx>(
 
     assert!(rows.iter().all(|r| r.len() == v.len()));
 
+    let key =
+        UsefulnessKey::new(matrix, v, witness_preference, hir_id, is_under_guard, is_top_level);
+    if let Some(usefulness) = state.cache.get(&key) {
+        debug!("cached: {:?}", usefulness);
+        return usefulness.clone();
+    }
+
+    // Generated code can contain matches that would take us forever to check. Past the budget, we
+    // give up and assume the worst: every arm is reachable and no arm covers `v`.
+    if state.budget == 0 {
+        state.is_over_budget = true;
+        let ret = match witness_preference {
+            ConstructWitness => {
+                let wildcards = v
+                    .iter()
+                    .rev()
+                    .map(|p| &*cx.pattern_arena.alloc(Pat::wildcard_from_ty(p.ty)))
+                    .collect();
+                WithWitnesses(vec![Witness(wildcards)])
+            }
+            LeaveOutWitness => NoWitnesses(SubPatSet::full()),
+        };
+        debug!("over budget: {:?}", ret);
+        return ret;
+    }
+    state.budget -= 1;
+    state.steps += 1;
+    state.depth += 1;
+    state.max_depth = state.max_depth.max(state.depth);
+    state.max_height = state.max_height.max(rows.len());
+    state.max_width = state.max_width.max(v.len());
+    // The key leaves out spans, so a call that lints can't be answered from the cache: the same
+    // sub-problem coming from other patterns must lint at their spans.
+    let had_lints = mem::replace(&mut state.has_lints, false);
+
     // FIXME(Nadrieril): Hack to work around type normalization issues (see #72476).
     let ty = matrix.heads().next().map_or(v.head().ty, |r| r.ty);
     let pcx = PatCtxt { cx, ty, span: v.head().span, is_top_level };
 
     // If the first pattern is an or-pattern, expand it.
-    let ret = if is_or_pat(v.head()) {
+    let mut ret = if is_or_pat(v.head()) {
         debug!("expanding or-pattern");
         let v_head = v.head();
         let vs: Vec<_> = v.expand_or_pat().collect();
@@ -1124,8 +1258,18 @@ fn is_useful<'p, 'tcx>(
         // We try each or-pattern branch in turn.
         let mut matrix = matrix.clone();
         let usefulnesses = vs.into_iter().enumerate().map(|(i, v)| {
-            let usefulness =
-                is_useful(cx, &matrix, &v, witness_preference, hir_id, is_under_guard, false);
+            let usefulness = ensure_sufficient_stack(|| {
+                is_useful(
+                    cx,
+                    &matrix,
+                    &v,
+                    witness_preference,
+                    hir_id,
+                    is_under_guard,
+                    false,
+                    state,
+                )
+            });
             // If pattern has a guard don't add it to the matrix.
             if !is_under_guard {
                 // We push the already-seen patterns into the matrix in order to detect redundant
@@ -1139,15 +1283,20 @@ fn is_useful<'p, 'tcx>(
         let v_ctor = v.head_ctor(cx);
         if let Constructor::IntRange(ctor_range) = &v_ctor {
             // Lint on likely incorrect range patterns (#63987)
-            ctor_range.lint_overlapping_range_endpoints(
+            if ctor_range.lint_overlapping_range_endpoints(
                 pcx,
                 matrix.head_ctors_and_spans(cx),
                 matrix.column_count().unwrap_or(0),
                 hir_id,
-            )
+            ) {
+                state.has_lints = true;
+            }
         }
         // We split the head constructor of `v`.
         let split_ctors = v_ctor.split(pcx, matrix.head_ctors(cx));
+        cx.dump_line(|| {
+            format!("matrix:{:?}v: {:?}\nsplit {:?} into {:?}", matrix, v, v_ctor, split_ctors)
+        });
         // For each constructor, we compute whether there's a value that starts with it that would
         // witness the usefulness of `v`.
         let start_matrix = &matrix;
@@ -1158,16 +1307,216 @@ fn is_useful<'p, 'tcx>(
             let spec_matrix =
                 start_matrix.specialize_constructor(pcx, &ctor, &ctor_wild_subpatterns);
             let v = v.pop_head_constructor(&ctor_wild_subpatterns);
-            let usefulness =
-                is_useful(cx, &spec_matrix, &v, witness_preference, hir_id, is_under_guard, false);
+            let usefulness = ensure_sufficient_stack(|| {
+                is_useful(
+                    cx,
+                    &spec_matrix,
+                    &v,
+                    witness_preference,
+                    hir_id,
+                    is_under_guard,
+                    false,
+                    state,
+                )
+            });
             usefulness.apply_constructor(pcx, start_matrix, &ctor, &ctor_wild_subpatterns)
         });
         Usefulness::merge(witness_preference, usefulnesses)
     };
+    if ret.truncate_witnesses() {
+        state.witnesses_truncated = true;
+    }
     debug!(?ret);
+    state.depth -= 1;
+    if !state.has_lints {
+        state.cache.insert(key, ret.clone());
+    }
+    state.has_lints |= had_lints;
     ret
 }
 
+/// State shared by all the calls to `is_useful` made while checking a single match.
+struct UsefulnessState<'p, 'tcx> {
+    /// The results of `is_useful` for the sub-problems seen so far. The same sub-problems come up
+    /// again and again, e.g. once for each branch of an or-pattern, which would otherwise make
+    /// some matches take exponential time to check.
+    cache: FxHashMap<UsefulnessKey<'tcx>, Usefulness<'p, 'tcx>>,
+    /// How many more sub-problems we are willing to solve before giving up.
+    budget: usize,
+    /// The budget of each search: the search for reachability and exhaustiveness, and the search
+    /// for witnesses, which only runs if the first one completed.
+    budget_per_search: usize,
+    /// Whether we ran out of budget, in which case the results are only approximations.
+    is_over_budget: bool,
+    /// Whether some witnesses were dropped because there were more than `WITNESS_LIMIT`.
+    witnesses_truncated: bool,
+    /// Whether we emitted lints along the way. Results computed while linting aren't cached, since
+    /// another match or sub-problem with the same patterns would need to lint at its own spans.
+    has_lints: bool,
+    /// The statistics reported by `-Z match-check-stats`: the number of sub-problems solved, the
+    /// current and largest depth of recursion, and the largest matrix seen.
+    steps: usize,
+    depth: usize,
+    max_depth: usize,
+    max_height: usize,
+    max_width: usize,
+}
+
+impl<'p, 'tcx> UsefulnessState<'p, 'tcx> {
+    fn new(cx: &MatchCheckCtxt<'p, 'tcx>) -> Self {
+        let budget = cx.tcx.sess.opts.debugging_opts.match_check_budget.unwrap_or(1_000_000);
+        UsefulnessState {
+            cache: FxHashMap::default(),
+            budget,
+            budget_per_search: budget,
+            is_over_budget: false,
+            witnesses_truncated: false,
+            has_lints: false,
+            steps: 0,
+            depth: 0,
+            max_depth: 0,
+            max_height: 0,
+            max_width: 0,
+        }
+    }
+}
+
+/// Identifies a call to `is_useful` in the cache of a `UsefulnessState`.
+#[derive(PartialEq, Eq, Hash)]
+struct UsefulnessKey<'tcx> {
+    /// The types of the columns, which patterns like `_` don't pin down.
+    tys: Vec<Ty<'tcx>>,
+    /// The rows of the matrix, followed by `v`.
+    pats: Vec<PatKeyPart<'tcx>>,
+    witness_preference: WitnessPreference,
+    hir_id: HirId,
+    is_under_guard: bool,
+    is_top_level: bool,
+}
+
+impl<'tcx> UsefulnessKey<'tcx> {
+    fn new<'p>(
+        matrix: &Matrix<'p, 'tcx>,
+        v: &PatStack<'p, 'tcx>,
+        witness_preference: WitnessPreference,
+        hir_id: HirId,
+        is_under_guard: bool,
+        is_top_level: bool,
+    ) -> Self {
+        let mut pats = Vec::new();
+        for row in &matrix.patterns {
+            for pat in row.iter() {
+                push_pat_key(&mut pats, pat, false);
+            }
+            pats.push(PatKeyPart::End);
+        }
+        for pat in v.iter() {
+            push_pat_key(&mut pats, pat, true);
+        }
+        UsefulnessKey {
+            tys: v.iter().map(|pat| pat.ty).collect(),
+            pats,
+            witness_preference,
+            hir_id,
+            is_under_guard,
+            is_top_level,
+        }
+    }
+}
+
+/// A step of the walk `push_pat_key` makes over a pattern. Every kind of pattern either has a
+/// fixed number of sub-patterns or brackets them, so a sequence of these identifies the patterns
+/// it was made from. Constants are interned, which makes comparing and hashing them cheap.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum PatKeyPart<'tcx> {
+    Wild,
+    Variant(usize),
+    Field(usize),
+    Deref,
+    Constant(&'tcx ty::Const<'tcx>),
+    Range { lo: &'tcx ty::Const<'tcx>, hi: &'tcx ty::Const<'tcx>, is_inclusive: bool },
+    /// Starts the fields of a `Variant` or a struct, the elements of a slice or array, or the
+    /// alternatives of an or-pattern.
+    Open,
+    Close,
+    /// The `..` of a slice pattern, followed by its binding if any.
+    Rest,
+    /// An or-pattern, with its address if it is part of `v`.
+    Or(Option<*const Pat<'tcx>>),
+    /// Ends a row of the matrix, or an arm.
+    End,
+    Guard,
+}
+
+/// Pushes what `is_useful` needs to know about a pattern: its structure, but not its spans or the
+/// names of its bindings. The or-patterns of `v` are also identified by their address, since the
+/// `SubPatSet`s computed for them point back at them.
+fn push_pat_key<'tcx>(
+    key: &mut Vec<PatKeyPart<'tcx>>,
+    pat: &Pat<'tcx>,
+    with_or_pat_addresses: bool,
+) {
+    ensure_sufficient_stack(|| push_pat_key_inner(key, pat, with_or_pat_addresses))
+}
+
+fn push_pat_key_inner<'tcx>(
+    key: &mut Vec<PatKeyPart<'tcx>>,
+    pat: &Pat<'tcx>,
+    with_or_pat_addresses: bool,
+) {
+    let push_field_pats = |key: &mut Vec<_>, subpatterns: &[FieldPat<'tcx>]| {
+        key.push(PatKeyPart::Open);
+        for field_pat in subpatterns {
+            key.push(PatKeyPart::Field(field_pat.field.index()));
+            push_pat_key(key, &field_pat.pattern, with_or_pat_addresses);
+        }
+        key.push(PatKeyPart::Close);
+    };
+    match &*pat.kind {
+        PatKind::Wild | PatKind::Binding { subpattern: None, .. } => key.push(PatKeyPart::Wild),
+        PatKind::Binding { subpattern: Some(subpattern), .. }
+        | PatKind::AscribeUserType { subpattern, .. } => {
+            push_pat_key(key, subpattern, with_or_pat_addresses)
+        }
+        PatKind::Variant { variant_index, subpatterns, .. } => {
+            key.push(PatKeyPart::Variant(variant_index.as_usize()));
+            push_field_pats(key, subpatterns);
+        }
+        PatKind::Leaf { subpatterns } => push_field_pats(key, subpatterns),
+        PatKind::Deref { subpattern } => {
+            key.push(PatKeyPart::Deref);
+            push_pat_key(key, subpattern, with_or_pat_addresses);
+        }
+        PatKind::Constant { value } => key.push(PatKeyPart::Constant(*value)),
+        PatKind::Range(PatRange { lo, hi, end }) => {
+            let is_inclusive = *end == RangeEnd::Included;
+            key.push(PatKeyPart::Range { lo: *lo, hi: *hi, is_inclusive });
+        }
+        PatKind::Slice { prefix, slice, suffix } | PatKind::Array { prefix, slice, suffix } => {
+            key.push(PatKeyPart::Open);
+            for pat in prefix {
+                push_pat_key(key, pat, with_or_pat_addresses);
+            }
+            if let Some(slice) = slice {
+                key.push(PatKeyPart::Rest);
+                push_pat_key(key, slice, with_or_pat_addresses);
+            }
+            for pat in suffix {
+                push_pat_key(key, pat, with_or_pat_addresses);
+            }
+            key.push(PatKeyPart::Close);
+        }
+        PatKind::Or { pats } => {
+            key.push(PatKeyPart::Or(with_or_pat_addresses.then_some(pat as *const _)));
+            key.push(PatKeyPart::Open);
+            for pat in pats {
+                push_pat_key(key, pat, with_or_pat_addresses);
+            }
+            key.push(PatKeyPart::Close);
+        }
+    }
+}
+
 /// The arm of a match expression.
 #[derive(Clone, Copy)]
 crate struct MatchArm<'p, 'tcx> {
@@ -1195,6 +1544,49 @@ fn is_useful<'p, 'tcx>(
     /// If the match is exhaustive, this is empty. If not, this contains witnesses for the lack of
     /// exhaustiveness.
     crate non_exhaustiveness_witnesses: Vec<Pat<'tcx>>,
+    /// Whether the match was too complex to check in full. In that case every arm is considered
+    /// reachable, and the match is only considered exhaustive if it has an unguarded catch-all arm.
+    crate is_incomplete: bool,
+    /// Whether `non_exhaustiveness_witnesses` only lists some of the missing patterns, because
+    /// there were too many of them to keep.
+    crate witnesses_truncated: bool,
+}
+
+/// Identifies a match in `MatchCheckCtxt::report_cache`.
+#[derive(PartialEq, Eq, Hash)]
+crate struct ReportKey<'tcx> {
+    scrut_ty: Ty<'tcx>,
+    /// The patterns of the arms as pushed by `push_pat_key`, each followed by whether the arm has
+    /// a guard.
+    arms: Vec<PatKeyPart<'tcx>>,
+    exhaustive_patterns: bool,
+}
+
+impl<'tcx> ReportKey<'tcx> {
+    fn new(
+        cx: &MatchCheckCtxt<'_, 'tcx>,
+        arms: &[MatchArm<'_, 'tcx>],
+        scrut_ty: Ty<'tcx>,
+    ) -> Self {
+        let mut key = Vec::new();
+        for arm in arms {
+            push_pat_key(&mut key, arm.pat, false);
+            if arm.has_guard {
+                key.push(PatKeyPart::Guard);
+            }
+            key.push(PatKeyPart::End);
+        }
+        ReportKey { scrut_ty, arms: key, exhaustive_patterns: cx.exhaustive_patterns }
+    }
+}
+
+/// A `UsefulnessReport` with the arms left out. Only reports that don't mention any spans are
+/// cached, so the arms only need to say whether they are reachable.
+crate struct CachedReport<'tcx> {
+    arm_is_reachable: Vec<bool>,
+    non_exhaustiveness_witnesses: Vec<Pat<'tcx>>,
+    is_incomplete: bool,
+    witnesses_truncated: bool,
 }
 
 /// The entrypoint for the usefulness algorithm. Computes whether a match is exhaustive and which
@@ -1208,14 +1600,50 @@ fn is_useful<'p, 'tcx>(
     scrut_hir_id: HirId,
     scrut_ty: Ty<'tcx>,
 ) -> UsefulnessReport<'p, 'tcx> {
+    cx.dump_line(|| {
+        format!("checking match on `{}` at {:?}", scrut_ty, cx.tcx.hir().span(scrut_hir_id))
+    });
+    let key = ReportKey::new(cx, arms, scrut_ty);
+    if let Some(cached) = cx.report_cache.borrow().get(&key) {
+        cx.dump_line(|| "same as a match checked before".to_string());
+        let arm_usefulness = arms
+            .iter()
+            .copied()
+            .zip(&cached.arm_is_reachable)
+            .map(|(arm, &is_reachable)| {
+                let reachability = if is_reachable {
+                    Reachability::Reachable(vec![])
+                } else {
+                    Reachability::Unreachable
+                };
+                (arm, reachability)
+            })
+            .collect();
+        return UsefulnessReport {
+            arm_usefulness,
+            non_exhaustiveness_witnesses: cached.non_exhaustiveness_witnesses.clone(),
+            is_incomplete: cached.is_incomplete,
+            witnesses_truncated: cached.witnesses_truncated,
+        };
+    }
+    let start_time = Instant::now();
     let mut matrix = Matrix::empty();
+    let mut state = UsefulnessState::new(cx);
     let arm_usefulness: Vec<_> = arms
         .iter()
         .copied()
         .map(|arm| {
             let v = PatStack::from_pattern(arm.pat);
-            let usefulness =
-                is_useful(cx, &matrix, &v, LeaveOutWitness, arm.hir_id, arm.has_guard, true);
+            let usefulness = is_useful(
+                cx,
+                &matrix,
+                &v,
+                LeaveOutWitness,
+                arm.hir_id,
+                arm.has_guard,
+                true,
+                &mut state,
+            );
             if !arm.has_guard {
                 matrix.push(v);
             }
@@ -1226,16 +1654,246 @@ fn is_useful<'p, 'tcx>(
                 }
                 WithWitnesses(..) => bug!(),
             };
+            cx.dump_line(|| format!("arm `{}`: {:?}", arm.pat, reachability));
             (arm, reachability)
         })
         .collect();
 
     let wild_pattern = cx.pattern_arena.alloc(Pat::wildcard_from_ty(scrut_ty));
     let v = PatStack::from_pattern(wild_pattern);
-    let usefulness = is_useful(cx, &matrix, &v, ConstructWitness, scrut_hir_id, false, true);
-    let non_exhaustiveness_witnesses = match usefulness {
-        WithWitnesses(pats) => pats.into_iter().map(|w| w.single_pattern()).collect(),
-        NoWitnesses(_) => bug!(),
+    // Most matches are exhaustive, so we first check that without building any witnesses, and only
+    // search again for witnesses when there is an error to report.
+    let is_exhaustive =
+        match is_useful(cx, &matrix, &v, LeaveOutWitness, scrut_hir_id, false, true, &mut state) {
+            NoWitnesses(subpats) => subpats.is_empty(),
+            WithWitnesses(..) => bug!(),
+        };
+    let non_exhaustiveness_witnesses = if is_exhaustive {
+        vec![]
+    } else if state.is_over_budget {
+        // The search above can't be trusted, so only accept a catch-all arm.
+        if arms.iter().any(|arm| !arm.has_guard && is_wildcard(arm.pat)) {
+            vec![]
+        } else {
+            vec![Pat::wildcard_from_ty(scrut_ty)]
+        }
+    } else {
+        // The reachability of the arms and the exhaustiveness of the match are known exactly by
+        // now. Building witnesses gets a budget of its own, and running out of it only costs us
+        // the details of what is missing.
+        state.budget = state.budget_per_search;
+        let usefulness =
+            is_useful(cx, &matrix, &v, ConstructWitness, scrut_hir_id, false, true, &mut state);
+        let witnesses_over_budget = mem::replace(&mut state.is_over_budget, false);
+        match usefulness {
+            WithWitnesses(_) if witnesses_over_budget => vec![Pat::wildcard_from_ty(scrut_ty)],
+            WithWitnesses(pats) => {
+                let witnesses = dedup_witnesses(pats.into_iter().map(|w| w.single_pattern()));
+                generalize_witnesses(cx, scrut_hir_id, witnesses)
+            }
+            NoWitnesses(_) => bug!(),
+        }
     };
-    UsefulnessReport { arm_usefulness, non_exhaustiveness_witnesses }
+    let is_incomplete = state.is_over_budget;
+    cx.dump_line(|| {
+        let witnesses: Vec<_> =
+            non_exhaustiveness_witnesses.iter().map(|w| w.to_string()).collect();
+        format!("missing patterns: {:?}, incomplete: {}", witnesses, is_incomplete)
+    });
+    record_match_check_stats(cx, scrut_hir_id, &state, start_time);
+    let witnesses_truncated = state.witnesses_truncated && !non_exhaustiveness_witnesses.is_empty();
+    // Arms with unreachable sub-patterns and lints point at the spans of this match.
+    let arm_is_reachable: Option<Vec<_>> = arm_usefulness
+        .iter()
+        .map(|(_, reachability)| match reachability {
+            Reachability::Reachable(unreachable_subpats) => {
+                unreachable_subpats.is_empty().then_some(true)
+            }
+            Reachability::Unreachable => Some(false),
+        })
+        .collect();
+    match arm_is_reachable {
+        Some(arm_is_reachable) if !state.has_lints => {
+            let cached = CachedReport {
+                arm_is_reachable,
+                non_exhaustiveness_witnesses: non_exhaustiveness_witnesses.clone(),
+                is_incomplete,
+                witnesses_truncated,
+            };
+            cx.report_cache.borrow_mut().insert(key, cached);
+        }
+        _ => {}
+    }
+    UsefulnessReport {
+        arm_usefulness,
+        non_exhaustiveness_witnesses,
+        is_incomplete,
+        witnesses_truncated,
+    }
+}
+
+/// A cheaper version of `compute_match_usefulness`, for matches that are expected to be fine:
+/// whether the match is known to be exhaustive, without computing the reachability of its arms.
+/// If this returns `false`, `compute_match_usefulness` gives the details.
+crate fn is_match_exhaustive<'p, 'tcx>(
+    cx: &MatchCheckCtxt<'p, 'tcx>,
+    arms: &[MatchArm<'p, 'tcx>],
+    scrut_hir_id: HirId,
+    scrut_ty: Ty<'tcx>,
+) -> bool {
+    let start_time = Instant::now();
+    let mut matrix = Matrix::empty();
+    for arm in arms.iter().filter(|arm| !arm.has_guard) {
+        matrix.push(PatStack::from_pattern(arm.pat));
+    }
+    let mut state = UsefulnessState::new(cx);
+    let wild_pattern = cx.pattern_arena.alloc(Pat::wildcard_from_ty(scrut_ty));
+    let v = PatStack::from_pattern(wild_pattern);
+    let is_exhaustive =
+        match is_useful(cx, &matrix, &v, LeaveOutWitness, scrut_hir_id, false, true, &mut state) {
+            NoWitnesses(subpats) => subpats.is_empty(),
+            WithWitnesses(..) => bug!(),
+        };
+    record_match_check_stats(cx, scrut_hir_id, &state, start_time);
+    is_exhaustive
+}
+
+/// Record how much work checking a match took, for `-Z match-check-stats`.
+fn record_match_check_stats<'p, 'tcx>(
+    cx: &MatchCheckCtxt<'p, 'tcx>,
+    scrut_hir_id: HirId,
+    state: &UsefulnessState<'p, 'tcx>,
+    start_time: Instant,
+) {
+    if !cx.tcx.sess.opts.debugging_opts.match_check_stats {
+        return;
+    }
+    let span = cx.tcx.hir().span(scrut_hir_id);
+    cx.tcx.sess.code_stats.record_match_check(MatchCheckInfo {
+        location: cx.tcx.sess.source_map().span_to_embeddable_string(span),
+        max_height: state.max_height,
+        max_width: state.max_width,
+        max_depth: state.max_depth,
+        steps: state.steps,
+        time: start_time.elapsed(),
+    });
+}
+
+/// Different branches of the search can reconstruct witnesses that only differ in positions the
+/// user can't observe, e.g. hidden fields or spans. Since witnesses are only ever used for
+/// diagnostics, we keep the first of each group of witnesses that render the same.
+fn dedup_witnesses<'tcx>(witnesses: impl Iterator<Item = Pat<'tcx>>) -> Vec<Pat<'tcx>> {
+    let mut seen = FxHashSet::default();
+    witnesses.filter(|witness| seen.insert(witness.to_string())).collect()
+}
+
+/// Witnesses are built one constructor at a time, so a sub-space that is missing entirely can be
+/// reported as several specific witnesses, e.g. `(Some(true), false)` and `(Some(false), false)`
+/// instead of `(Some(_), false)`. Merge witnesses that only differ in one nested pattern into a
+/// single witness with a wildcard there, whenever the differing patterns cover every value. The
+/// result still only stands for values that no arm matches. Shallower positions are generalized
+/// first, and only nested positions are, so that the variants of a top-level `enum` are still
+/// listed one by one.
+fn generalize_witnesses<'p, 'tcx>(
+    cx: &MatchCheckCtxt<'p, 'tcx>,
+    hir_id: HirId,
+    mut witnesses: Vec<Pat<'tcx>>,
+) -> Vec<Pat<'tcx>> {
+    'merge: loop {
+        // Group the witnesses by a nested position and how they render with a wildcard there.
+        let mut groups: Vec<(Vec<usize>, Vec<usize>)> = Vec::new();
+        let mut group_indices: FxHashMap<(Vec<usize>, String), usize> = FxHashMap::default();
+        for (witness_index, witness) in witnesses.iter().enumerate() {
+            for path in nested_pattern_paths(witness) {
+                let mut generalized = witness.clone();
+                let nested = nested_pattern_at(&mut generalized, &path);
+                if is_wildcard(nested) {
+                    continue;
+                }
+                *nested = Pat::wildcard_from_ty(nested.ty);
+                let group_index = *group_indices
+                    .entry((path.clone(), generalized.to_string()))
+                    .or_insert_with(|| {
+                        groups.push((path, Vec::new()));
+                        groups.len() - 1
+                    });
+                groups[group_index].1.push(witness_index);
+            }
+        }
+        groups.sort_by_key(|(path, _)| path.len());
+
+        for (path, members) in groups {
+            if members.len() < 2 {
+                continue;
+            }
+            let nested: Vec<_> = members
+                .iter()
+                .map(|&i| nested_pattern_at(&mut witnesses[i], &path).clone())
+                .collect();
+            if !covers_all_values(cx, hir_id, &nested) {
+                continue;
+            }
+            let nested = nested_pattern_at(&mut witnesses[members[0]], &path);
+            *nested = Pat::wildcard_from_ty(nested.ty);
+            for &i in members[1..].iter().rev() {
+                witnesses.remove(i);
+            }
+            continue 'merge;
+        }
+        return dedup_witnesses(witnesses.into_iter());
+    }
+}
+
+/// Whether the patterns together match every value of their type.
+fn covers_all_values<'p, 'tcx>(
+    cx: &MatchCheckCtxt<'p, 'tcx>,
+    hir_id: HirId,
+    pats: &[Pat<'tcx>],
+) -> bool {
+    let mut matrix = Matrix::empty();
+    for pat in pats {
+        matrix.push(PatStack::from_pattern(cx.pattern_arena.alloc(pat.clone())));
+    }
+    let wild_pattern = cx.pattern_arena.alloc(Pat::wildcard_from_ty(pats[0].ty));
+    let v = PatStack::from_pattern(wild_pattern);
+    let mut state = UsefulnessState::new(cx);
+    match is_useful(cx, &matrix, &v, LeaveOutWitness, hir_id, false, false, &mut state) {
+        NoWitnesses(subpats) => subpats.is_empty(),
+        WithWitnesses(..) => bug!(),
+    }
+}
+
+/// The patterns directly nested in a witness, in the order they are printed.
+fn nested_patterns<'a, 'tcx>(pat: &'a mut Pat<'tcx>) -> Vec<&'a mut Pat<'tcx>> {
+    match &mut *pat.kind {
+        PatKind::Variant { subpatterns, .. } | PatKind::Leaf { subpatterns } => {
+            subpatterns.iter_mut().map(|field_pat| &mut field_pat.pattern).collect()
+        }
+        PatKind::Deref { subpattern } => vec![subpattern],
+        PatKind::Slice { prefix, slice, suffix } | PatKind::Array { prefix, slice, suffix } => {
+            prefix.iter_mut().chain(slice.iter_mut()).chain(suffix.iter_mut()).collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// The paths to all the patterns nested in a witness, shallowest first. A path lists the index
+/// of the nested pattern to descend into at each level.
+fn nested_pattern_paths(pat: &Pat<'_>) -> Vec<Vec<usize>> {
+    fn collect(pat: &mut Pat<'_>, path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+        for (i, nested) in nested_patterns(pat).into_iter().enumerate() {
+            path.push(i);
+            paths.push(path.clone());
+            collect(nested, path, paths);
+            path.pop();
+        }
+    }
+    let mut paths = Vec::new();
+    collect(&mut pat.clone(), &mut Vec::new(), &mut paths);
+    paths.sort_by_key(|path| path.len());
+    paths
+}
+
+fn nested_pattern_at<'a, 'tcx>(pat: &'a mut Pat<'tcx>, path: &[usize]) -> &'a mut Pat<'tcx> {
+    path.iter().fold(pat, |pat, &i| nested_patterns(pat).swap_remove(i))
 }
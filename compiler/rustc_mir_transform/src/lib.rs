@@ -67,8 +67,10 @@
 mod separate_const_switch;
 mod shim;
 mod simplify;
+mod simplify_bool_switch;
 mod simplify_branches;
 mod simplify_comparison_integral;
+mod simplify_range_comparison;
 mod simplify_try;
 mod uninhabited_enum_branching;
 mod unreachable_prop;
@@ -515,6 +517,9 @@ fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
         &early_otherwise_branch::EarlyOtherwiseBranch,
         &simplify_comparison_integral::SimplifyComparisonIntegral,
+        // after const prop, which would lint about the wrapping subtraction on known values
+        &simplify_range_comparison::SimplifyRangeComparison,
+        &simplify_bool_switch::SimplifyBoolSwitch,
         &simplify_try::SimplifyArmIdentity,
         &simplify_try::SimplifyBranchSame,
         &dest_prop::DestinationPropagation,
@@ -0,0 +1,168 @@
+use crate::MirPass;
+use rustc_middle::mir::interpret::Scalar;
+use rustc_middle::mir::*;
+use rustc_middle::ty::TyCtxt;
+
+/// Pass to merge a switch on a boolean whose targets both immediately switch on the same second
+/// boolean, as emitted for matches on `(bool, bool)`, into a single switch. For an example, it
+/// turns something like
+///
+/// ```text
+/// bb0: {
+///     switchInt(_1) -> [false: bb1, otherwise: bb2];
+/// }
+///
+/// bb1: {
+///     switchInt(_2) -> [false: bb3, otherwise: bb4];
+/// }
+///
+/// bb2: {
+///     switchInt(_2) -> [false: bb5, otherwise: bb6];
+/// }
+/// ```
+///
+/// into:
+///
+/// ```text
+/// bb0: {
+///     _3 = _1 as u8 (Misc);
+///     _4 = _2 as u8 (Misc);
+///     _5 = Shl(move _3, const 1_u8);
+///     _6 = BitOr(move _5, move _4);
+///     switchInt(move _6) -> [0_u8: bb3, 1_u8: bb4, 2_u8: bb5, otherwise: bb6];
+/// }
+/// ```
+///
+/// `bb1` and `bb2` are left in place, `SimplifyCfg` removes them if nothing else jumps to them.
+pub struct SimplifyBoolSwitch;
+
+impl<'tcx> MirPass<'tcx> for SimplifyBoolSwitch {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.mir_opt_level() < 3 {
+            return;
+        }
+        trace!("Running SimplifyBoolSwitch on {:?}", body.source);
+
+        let opts: Vec<_> = body
+            .basic_blocks()
+            .indices()
+            .filter_map(|bb| find_bool_pair(body, bb).map(|opt| (bb, opt)))
+            .collect();
+        for (bb, opt) in opts {
+            if !tcx.consider_optimizing(|| format!("SimplifyBoolSwitch {:?}", &opt)) {
+                break;
+            }
+            trace!("SUCCESS: Applying {:?} to {:?}", opt, bb);
+            apply(tcx, body, bb, opt);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BoolPair<'tcx> {
+    first: Operand<'tcx>,
+    second: Place<'tcx>,
+    /// The blocks for `(false, false)`, `(false, true)`, `(true, false)` and `(true, true)`.
+    targets: [BasicBlock; 4],
+    source_info: SourceInfo,
+}
+
+/// Returns the operand switched on and the blocks for `false` and `true` if `terminator` is a
+/// two-way branch on a boolean.
+fn bool_switch<'a, 'tcx>(
+    terminator: &'a Terminator<'tcx>,
+) -> Option<(&'a Operand<'tcx>, BasicBlock, BasicBlock)> {
+    match &terminator.kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, targets } if switch_ty.is_bool() => {
+            let mut values = targets.iter();
+            match (values.next(), values.next()) {
+                (Some((0, if_false)), None) => Some((discr, if_false, targets.otherwise())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn find_bool_pair<'tcx>(body: &Body<'tcx>, bb: BasicBlock) -> Option<BoolPair<'tcx>> {
+    let terminator = body.basic_blocks()[bb].terminator();
+    let (first, if_false, if_true) = bool_switch(terminator)?;
+    if if_false == if_true || if_false == bb || if_true == bb {
+        return None;
+    }
+
+    // Both targets must do nothing but switch on the same place, so that it can be read before
+    // the first switch instead.
+    let second_switch = |bb: BasicBlock| {
+        let block = &body.basic_blocks()[bb];
+        if !block.statements.is_empty() {
+            return None;
+        }
+        match bool_switch(block.terminator())? {
+            (Operand::Copy(place) | Operand::Move(place), if_false, if_true) => {
+                Some((*place, if_false, if_true))
+            }
+            (Operand::Constant(_), ..) => None,
+        }
+    };
+    let (second, ff, ft) = second_switch(if_false)?;
+    let (true_second, tf, tt) = second_switch(if_true)?;
+    if second != true_second {
+        return None;
+    }
+    // The two booleans are usually fields of the same tuple, which is fine: moving one field out
+    // leaves the other one readable. Only switching twice on the same place is left alone.
+    if let Operand::Copy(first) | Operand::Move(first) = first {
+        if *first == second {
+            return None;
+        }
+    }
+
+    Some(BoolPair {
+        first: first.clone(),
+        second,
+        targets: [ff, ft, tf, tt],
+        source_info: terminator.source_info,
+    })
+}
+
+fn apply<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, bb: BasicBlock, opt: BoolPair<'tcx>) {
+    let source_info = opt.source_info;
+    let span = source_info.span;
+    let u8_ty = tcx.types.u8;
+
+    let first = body.local_decls.push(LocalDecl::new(u8_ty, span));
+    let second = body.local_decls.push(LocalDecl::new(u8_ty, span));
+    let shifted = body.local_decls.push(LocalDecl::new(u8_ty, span));
+    let packed = body.local_decls.push(LocalDecl::new(u8_ty, span));
+    let one = Operand::const_from_scalar(tcx, u8_ty, Scalar::from_u8(1), span);
+
+    let assign = |place: Local, rvalue| Statement {
+        source_info,
+        kind: StatementKind::Assign(Box::new((place.into(), rvalue))),
+    };
+    let statements = vec![
+        assign(first, Rvalue::Cast(CastKind::Misc, opt.first, u8_ty)),
+        assign(second, Rvalue::Cast(CastKind::Misc, Operand::Copy(opt.second), u8_ty)),
+        assign(
+            shifted,
+            Rvalue::BinaryOp(BinOp::Shl, Box::new((Operand::Move(first.into()), one))),
+        ),
+        assign(
+            packed,
+            Rvalue::BinaryOp(
+                BinOp::BitOr,
+                Box::new((Operand::Move(shifted.into()), Operand::Move(second.into()))),
+            ),
+        ),
+    ];
+
+    let [ff, ft, tf, tt] = opt.targets;
+    let block = &mut body.basic_blocks_mut()[bb];
+    block.statements.extend(statements);
+    block.terminator_mut().kind = TerminatorKind::SwitchInt {
+        discr: Operand::Move(packed.into()),
+        switch_ty: u8_ty,
+        targets: SwitchTargets::new(vec![(0, ff), (1, ft), (2, tf)].into_iter(), tt),
+    };
+}
@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+
+use crate::MirPass;
+use rustc_middle::mir::interpret::Scalar;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, IntTy, ParamEnv, Ty, TyCtxt, UintTy};
+use rustc_target::abi::Size;
+
+/// Pass to turn the two comparisons testing whether an integer or `char` lies in a range, as
+/// emitted for range patterns, into a single one. For an example, it turns something like
+///
+/// ```text
+/// bb0: {
+///     _3 = Le(const 10_i32, _1);
+///     switchInt(move _3) -> [false: bb2, otherwise: bb1];
+/// }
+///
+/// bb1: {
+///     _4 = Le(_1, const 20_i32);
+///     switchInt(move _4) -> [false: bb2, otherwise: bb3];
+/// }
+/// ```
+///
+/// into:
+///
+/// ```text
+/// bb0: {
+///     _5 = _1 as u32 (Misc);
+///     _6 = Sub(move _5, const 10_u32);
+///     _3 = Le(move _6, const 10_u32);
+///     switchInt(move _3) -> [false: bb2, otherwise: bb3];
+/// }
+/// ```
+///
+/// The subtraction wraps around, so values below the start of the range become larger than its
+/// length and are rejected by the same comparison. `bb1` is left in place, `SimplifyCfg` removes
+/// it if nothing else jumps to it.
+pub struct SimplifyRangeComparison;
+
+impl<'tcx> MirPass<'tcx> for SimplifyRangeComparison {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.mir_opt_level() < 3 {
+            return;
+        }
+        trace!("Running SimplifyRangeComparison on {:?}", body.source);
+
+        let param_env = tcx.param_env(body.source.def_id());
+        let opts: Vec<_> = body
+            .basic_blocks()
+            .indices()
+            .filter_map(|bb| find_range_test(tcx, param_env, body, bb).map(|opt| (bb, opt)))
+            .collect();
+        for (bb, opt) in opts {
+            if !tcx.consider_optimizing(|| format!("SimplifyRangeComparison {:?}", &opt)) {
+                break;
+            }
+            trace!("SUCCESS: Applying {:?} to {:?}", opt, bb);
+            apply(tcx, body, bb, opt);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RangeTest<'tcx> {
+    /// The boolean the first comparison is assigned to and the first block switches on.
+    cond: Local,
+    /// The place compared with both ends of the range.
+    place: Place<'tcx>,
+    ty: Ty<'tcx>,
+    size: Size,
+    /// The start of the range.
+    lo: u128,
+    /// The number of values in the range, minus one.
+    len: u128,
+    success: BasicBlock,
+    failure: BasicBlock,
+    source_info: SourceInfo,
+}
+
+/// Returns the local switched on and the blocks for `false` and `true` if `terminator` branches on
+/// a boolean it moves out of.
+fn bool_switch(terminator: &Terminator<'_>) -> Option<(Local, BasicBlock, BasicBlock)> {
+    match &terminator.kind {
+        TerminatorKind::SwitchInt { discr: Operand::Move(place), switch_ty, targets }
+            if switch_ty.is_bool() =>
+        {
+            let mut values = targets.iter();
+            match (values.next(), values.next()) {
+                (Some((0, failure)), None) => {
+                    Some((place.as_local()?, failure, targets.otherwise()))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn find_range_test<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    bb: BasicBlock,
+) -> Option<RangeTest<'tcx>> {
+    let block = &body.basic_blocks()[bb];
+    let (cond, failure, next) = bool_switch(block.terminator())?;
+    let stmt = block.statements.last()?;
+    let (lo, place) = match &stmt.kind {
+        StatementKind::Assign(box (
+            lhs,
+            Rvalue::BinaryOp(BinOp::Le, box (Operand::Constant(lo), Operand::Copy(place))),
+        )) if lhs.as_local() == Some(cond) => (lo, *place),
+        _ => return None,
+    };
+
+    // The second block must only do the other comparison, so that nothing can change `place` in
+    // between and skipping it loses nothing.
+    let next_block = &body.basic_blocks()[next];
+    let (next_cond, next_failure, success) = bool_switch(next_block.terminator())?;
+    if next_failure != failure {
+        return None;
+    }
+    let next_stmt = match &next_block.statements[..] {
+        [next_stmt] => next_stmt,
+        _ => return None,
+    };
+    let (op, hi) = match &next_stmt.kind {
+        StatementKind::Assign(box (
+            lhs,
+            Rvalue::BinaryOp(
+                op @ (BinOp::Le | BinOp::Lt),
+                box (Operand::Copy(hi_place) | Operand::Move(hi_place), Operand::Constant(hi)),
+            ),
+        )) if lhs.as_local() == Some(next_cond) && *hi_place == place => (*op, hi),
+        _ => return None,
+    };
+
+    let ty = place.ty(body, tcx).ty;
+    if !(ty.is_integral() || ty.is_char()) || lo.ty() != ty || hi.ty() != ty {
+        return None;
+    }
+    let size = tcx.layout_of(param_env.and(ty)).ok()?.size;
+    let lo_bits = lo.literal.try_eval_bits(tcx, param_env, ty)?;
+    let hi_bits = hi.literal.try_eval_bits(tcx, param_env, ty)?;
+
+    // Comparisons written by hand may describe an empty range, there is nothing to fold then.
+    let ordering = if ty.is_signed() {
+        (size.sign_extend(lo_bits) as i128).cmp(&(size.sign_extend(hi_bits) as i128))
+    } else {
+        lo_bits.cmp(&hi_bits)
+    };
+    let hi_bits = match (op, ordering) {
+        (BinOp::Le, Ordering::Less | Ordering::Equal) => hi_bits,
+        (BinOp::Lt, Ordering::Less) => size.truncate(hi_bits.wrapping_sub(1)),
+        _ => return None,
+    };
+
+    Some(RangeTest {
+        cond,
+        place,
+        ty,
+        size,
+        lo: lo_bits,
+        len: size.truncate(hi_bits.wrapping_sub(lo_bits)),
+        success,
+        failure,
+        source_info: stmt.source_info,
+    })
+}
+
+fn apply<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, bb: BasicBlock, opt: RangeTest<'tcx>) {
+    let source_info = opt.source_info;
+    let span = source_info.span;
+    let unsigned_ty = match *opt.ty.kind() {
+        ty::Int(ity) => tcx.mk_mach_uint(match ity {
+            IntTy::Isize => UintTy::Usize,
+            IntTy::I8 => UintTy::U8,
+            IntTy::I16 => UintTy::U16,
+            IntTy::I32 => UintTy::U32,
+            IntTy::I64 => UintTy::U64,
+            IntTy::I128 => UintTy::U128,
+        }),
+        ty::Char => tcx.types.u32,
+        _ => opt.ty,
+    };
+
+    let mut statements = Vec::with_capacity(3);
+    let value = if unsigned_ty == opt.ty {
+        Operand::Copy(opt.place)
+    } else {
+        let cast = body.local_decls.push(LocalDecl::new(unsigned_ty, span));
+        statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(Box::new((
+                cast.into(),
+                Rvalue::Cast(CastKind::Misc, Operand::Copy(opt.place), unsigned_ty),
+            ))),
+        });
+        Operand::Move(cast.into())
+    };
+    let offset = body.local_decls.push(LocalDecl::new(unsigned_ty, span));
+    let lo =
+        Operand::const_from_scalar(tcx, unsigned_ty, Scalar::from_uint(opt.lo, opt.size), span);
+    statements.push(Statement {
+        source_info,
+        kind: StatementKind::Assign(Box::new((
+            offset.into(),
+            Rvalue::BinaryOp(BinOp::Sub, Box::new((value, lo))),
+        ))),
+    });
+    let len =
+        Operand::const_from_scalar(tcx, unsigned_ty, Scalar::from_uint(opt.len, opt.size), span);
+    statements.push(Statement {
+        source_info,
+        kind: StatementKind::Assign(Box::new((
+            opt.cond.into(),
+            Rvalue::BinaryOp(BinOp::Le, Box::new((Operand::Move(offset.into()), len))),
+        ))),
+    });
+
+    let block = &mut body.basic_blocks_mut()[bb];
+    block.statements.pop();
+    block.statements.extend(statements);
+    block.terminator_mut().kind =
+        TerminatorKind::if_(tcx, Operand::Move(opt.cond.into()), opt.success, opt.failure);
+}
@@ -2187,7 +2187,21 @@ pub(super) fn parse_arm(&mut self) -> PResult<'a, Arm> {
                 None
             };
             let arrow_span = this.token.span;
-            this.expect(&token::FatArrow)?;
+            if this.token == token::RArrow {
+                // Recover from `pat -> expr`, as written in some other languages, and keep
+                // parsing the rest of the `match`.
+                this.struct_span_err(arrow_span, "expected `=>`, found `->`")
+                    .span_suggestion(
+                        arrow_span,
+                        "use `=>` to separate the pattern from the arm body",
+                        "=>".to_string(),
+                        Applicability::MachineApplicable,
+                    )
+                    .emit();
+                this.bump();
+            } else {
+                this.expect(&token::FatArrow)?;
+            }
             let arm_start_span = this.token.span;
 
             let expr = this.parse_expr_res(Restrictions::STMT_EXPR, None).map_err(|mut err| {
@@ -2217,6 +2231,30 @@ pub(super) fn parse_arm(&mut self) -> PResult<'a, Arm> {
                         TrailingToken::None,
                     ));
                 }
+                if this.token != token::Comma && this.looks_like_arm_start() {
+                    // The comma after a non-block arm is missing, but the next arm parses on its
+                    // own. Report the comma and carry on with that arm.
+                    this.struct_span_err(this.token.span, "expected `,` following `match` arm")
+                        .span_suggestion_short(
+                            hi.shrink_to_hi(),
+                            "missing a comma here to end this `match` arm",
+                            ",".to_owned(),
+                            Applicability::MachineApplicable,
+                        )
+                        .emit();
+                    return Ok((
+                        ast::Arm {
+                            attrs: attrs.into(),
+                            pat,
+                            guard,
+                            body: expr,
+                            span: lo.to(hi),
+                            id: DUMMY_NODE_ID,
+                            is_placeholder: false,
+                        },
+                        TrailingToken::None,
+                    ));
+                }
                 this.expect_one_of(&[token::Comma], &[token::CloseDelim(token::Brace)]).map_err(
                     |mut err| {
                         match (sm.span_to_lines(expr.span), sm.span_to_lines(arm_start_span)) {
@@ -2273,6 +2311,38 @@ pub(super) fn parse_arm(&mut self) -> PResult<'a, Arm> {
         })
     }
 
+    /// Whether the tokens from the current one on look like the start of a `match` arm, that is a
+    /// few tokens of a pattern followed by `=>` or by an `if` guard. This only looks ahead, so that
+    /// it is cheap and reports nothing. A delimited group counts as a single token, so `Some(..)`
+    /// or `S { .. }` only take one step.
+    fn looks_like_arm_start(&self) -> bool {
+        // Enough for a path with generic arguments, or a few alternatives.
+        const MAX_PATTERN_TOKENS: usize = 16;
+
+        let ends_arm_start = |token: &Token| {
+            matches!(
+                token.kind,
+                token::FatArrow | token::Comma | token::Semi | token::CloseDelim(_) | token::Eof
+            ) || token.is_keyword(kw::If)
+        };
+        if ends_arm_start(&self.token) {
+            return false;
+        }
+        for dist in 1..=MAX_PATTERN_TOKENS {
+            let found = self.look_ahead(dist, |token| {
+                if !ends_arm_start(token) {
+                    None
+                } else {
+                    Some(*token == token::FatArrow || token.is_keyword(kw::If))
+                }
+            });
+            if let Some(found) = found {
+                return found;
+            }
+        }
+        false
+    }
+
     /// Parses a `try {...}` expression (`try` token already eaten).
     fn parse_try_block(&mut self, span_lo: Span, mut attrs: AttrVec) -> PResult<'a, P<Expr>> {
         let (iattrs, body) = self.parse_inner_attrs_and_block()?;
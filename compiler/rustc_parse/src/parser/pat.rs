@@ -426,6 +426,14 @@ fn parse_pat_with_range_pat(
             PatKind::Wild
         } else if self.eat_keyword(kw::Mut) {
             self.parse_pat_ident_mut()?
+        } else if self.token.is_keyword(kw::Let)
+            && self.look_ahead(1, |t| {
+                t.can_begin_expr() || t.is_keyword(kw::Mut) || t.is_keyword(kw::Ref)
+            })
+        {
+            // Recover from `let` in pattern position, e.g. `match x { let Some(y) => .. }`.
+            self.recover_let_in_pat();
+            return self.parse_pat_with_range_pat(allow_range_pat, expected);
         } else if self.eat_keyword(kw::Ref) {
             // Parse ref ident @ pat / ref mut ident @ pat
             let mutbl = self.parse_mutability();
@@ -721,6 +729,20 @@ fn ban_mut_general_pat(&self, lo: Span, pat: &Pat, changed_any_binding: bool) {
             .emit();
     }
 
+    /// Error on a `let` keyword where a pattern should start, and eat it.
+    fn recover_let_in_pat(&mut self) {
+        let span = self.token.span.until(self.look_ahead(1, |t| t.span));
+        self.struct_span_err(span, "expected pattern, found `let`")
+            .span_suggestion(
+                span,
+                "remove the unnecessary `let` keyword",
+                String::new(),
+                Applicability::MachineApplicable,
+            )
+            .emit();
+        self.bump();
+    }
+
     /// Eat any extraneous `mut`s and error + recover if we ate any.
     fn recover_additional_muts(&mut self) {
         let lo = self.token.span;
@@ -1027,6 +1027,15 @@ fn build_reduced_graph_for_external_crate_res(&mut self, child: Export) {
                 let field_names = cstore.struct_field_names_untracked(def_id, self.r.session);
                 self.insert_field_names(def_id, field_names);
             }
+            Res::Def(DefKind::Ctor(CtorOf::Variant, CtorKind::Fn), def_id) => {
+                // The fields are recorded on the variant, which is the parent of its constructor.
+                if let Some(index) = cstore.def_key(def_id).parent {
+                    let variant_def_id = DefId { index, ..def_id };
+                    let field_names =
+                        cstore.struct_field_names_untracked(variant_def_id, self.r.session);
+                    self.insert_field_names(def_id, field_names);
+                }
+            }
             Res::Def(DefKind::AssocFn, def_id) => {
                 if cstore
                     .associated_item_cloned_untracked(def_id, self.r.session)
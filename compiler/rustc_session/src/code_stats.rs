@@ -1,7 +1,9 @@
 use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::profiling::duration_to_secs_str;
 use rustc_data_structures::sync::Lock;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -45,9 +47,24 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+/// The cost of checking one `match` (or irrefutable pattern) for exhaustiveness.
+#[derive(Debug)]
+pub struct MatchCheckInfo {
+    pub location: String,
+    /// The largest number of rows and columns of the pattern matrices considered.
+    pub max_height: usize,
+    pub max_width: usize,
+    /// How deep the usefulness algorithm recursed.
+    pub max_depth: usize,
+    /// How many sub-problems the usefulness algorithm solved.
+    pub steps: usize,
+    pub time: Duration,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    match_checks: Lock<Vec<MatchCheckInfo>>,
 }
 
 impl CodeStats {
@@ -190,4 +207,33 @@ pub fn print_type_sizes(&self) {
             }
         }
     }
+
+    pub fn record_match_check(&self, info: MatchCheckInfo) {
+        self.match_checks.borrow_mut().push(info);
+    }
+
+    pub fn print_match_check_stats(&self) {
+        let mut match_checks = self.match_checks.borrow_mut();
+        let total_time: Duration = match_checks.iter().map(|info| info.time).sum();
+        println!("match-check-stats: {} matches checked", match_checks.len());
+        println!("match-check-stats: total time: {}", duration_to_secs_str(total_time));
+        let max = |f: fn(&MatchCheckInfo) -> usize| match_checks.iter().map(f).max().unwrap_or(0);
+        println!("match-check-stats: max matrix height: {}", max(|info| info.max_height));
+        println!("match-check-stats: max matrix width: {}", max(|info| info.max_width));
+        println!("match-check-stats: max recursion depth: {}", max(|info| info.max_depth));
+
+        // Point at the matches that dominate the time spent.
+        match_checks.sort_by(|info1, info2| info2.time.cmp(&info1.time));
+        for info in match_checks.iter().take(10) {
+            println!(
+                "match-check-stats: {}: {}, {} steps, {}x{} matrix, depth {}",
+                info.location,
+                duration_to_secs_str(info.time),
+                info.steps,
+                info.max_height,
+                info.max_width,
+                info.max_depth,
+            );
+        }
+    }
 }
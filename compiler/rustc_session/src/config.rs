@@ -2188,6 +2188,7 @@ fn parse_pretty(debugging_opts: &DebuggingOptions, efmt: ErrorOutputType) -> Opt
         "thir-tree" => ThirTree,
         "mir" => Mir,
         "mir-cfg" => MirCFG,
+        "match-decision-tree" => MatchDecisionTree,
         name => early_error(
             efmt,
             &format!(
@@ -2195,7 +2196,8 @@ fn parse_pretty(debugging_opts: &DebuggingOptions, efmt: ErrorOutputType) -> Opt
                             `expanded`, `identified`, `expanded,identified`, \
                             `expanded,hygiene`, `everybody_loops`, \
                             `ast-tree`, `ast-tree,expanded`, `hir`, `hir,identified`, \
-                            `hir,typed`, `hir-tree`, `mir` or `mir-cfg`; got {}",
+                            `hir,typed`, `hir-tree`, `mir`, `mir-cfg` or \
+                            `match-decision-tree`; got {}",
                 name
             ),
         ),
@@ -2357,6 +2359,8 @@ pub enum PpMode {
     Mir,
     /// `-Zunpretty=mir-cfg`
     MirCFG,
+    /// `-Zunpretty=match-decision-tree`
+    MatchDecisionTree,
 }
 
 impl PpMode {
@@ -2372,13 +2376,14 @@ pub fn needs_ast_map(&self) -> bool {
             | HirTree
             | ThirTree
             | Mir
-            | MirCFG => true,
+            | MirCFG
+            | MatchDecisionTree => true,
         }
     }
 
     pub fn needs_analysis(&self) -> bool {
         use PpMode::*;
-        matches!(*self, Mir | MirCFG | ThirTree)
+        matches!(*self, Mir | MirCFG | ThirTree | MatchDecisionTree)
     }
 }
 
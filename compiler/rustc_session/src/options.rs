@@ -1058,6 +1058,10 @@ mod parse {
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
+    dump_match_check: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "dump the pattern matrices, constructor splits and arm reachability computed while \
+        checking the matches of the functions whose path contains `val` (`all` for every function, \
+        several filters can be separated by `|`) to files in `-Z dump-mir-dir`"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
@@ -1154,6 +1158,12 @@ mod parse {
         "list the symbols defined by a library crate (default: no)"),
     macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
         "show macro backtraces (default: no)"),
+    match_check_budget: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "how many steps exhaustiveness checking may take on a single `match` before giving up, \
+        and again to list the missing patterns (default: 1000000)"),
+    match_check_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print statistics about exhaustiveness checking after compilation, including the matches \
+        that took the longest to check (default: no)"),
     merge_functions: Option<MergeFunctions> = (None, parse_merge_functions, [TRACKED],
         "control the operation of the MergeFunctions LLVM pass, taking \
         the same values as the target option of the same name"),
@@ -1349,6 +1359,9 @@ mod parse {
         "adds unstable command line options to rustc interface (default: no)"),
     use_ctors_section: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "use legacy .ctors section for initializers rather than .init_array"),
+    validate_match_lowering: bool = (false, parse_bool, [UNTRACKED],
+        "check that the decision trees of matches agree with match checking, and ICE if not; \
+        implied by `-Z validate-mir` (default: no)"),
     validate_mir: bool = (false, parse_bool, [UNTRACKED],
         "validate MIR after each transformation"),
     verbose: bool = (false, parse_bool, [UNTRACKED],
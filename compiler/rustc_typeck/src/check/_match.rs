@@ -183,7 +183,8 @@ pub fn check_match(
 
             other_arms.push(arm_span);
             if other_arms.len() > 5 {
-                other_arms.remove(0);
+                // Keep the first arm, it is pointed at when reporting a mismatch in a later one.
+                other_arms.remove(1);
             }
             prior_arm_ty = Some(arm_ty);
         }
@@ -1117,11 +1117,13 @@ fn e0023(
             // or the pattern consists of all wildcards.
             if fields.len() - subpats.len() > 1 || all_wildcards {
                 if subpats.is_empty() || all_wildcards {
+                    // The pattern ignores every field anyway, so this doesn't need to guess where
+                    // the missing fields go.
                     err.span_suggestion_verbose(
                         all_fields_span,
                         "use `..` to ignore all fields",
                         String::from(".."),
-                        Applicability::MaybeIncorrect,
+                        Applicability::MachineApplicable,
                     );
                 } else {
                     err.span_suggestion_verbose(
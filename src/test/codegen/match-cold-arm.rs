@@ -0,0 +1,61 @@
+// compile-flags: -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+
+// A match arm that can only panic is marked as the unlikely side of the branch.
+
+#[no_mangle]
+pub fn cold_arm(x: u32) -> u32 {
+    // CHECK: call i1 @llvm.expect.i1(i1 %{{.*}}, i1 false)
+    match x {
+        0 => panic!("zero"),
+        _ => x,
+    }
+}
+
+#[no_mangle]
+pub fn cold_otherwise(x: bool) -> u32 {
+    // CHECK: call i1 @llvm.expect.i1(i1 %{{.*}}, i1 true)
+    match x {
+        true => 1,
+        false => unreachable!(),
+    }
+}
+
+// The same goes for the arms of a switch with more targets, which get branch weights instead.
+
+#[no_mangle]
+pub fn cold_switch_arm(x: u32) -> u32 {
+    // CHECK-LABEL: @cold_switch_arm
+    // CHECK: switch i32 %{{.*}}, label %{{.*}} [
+    // CHECK: ], !prof ![[COLD_ARM:[0-9]+]]
+    match x {
+        0 => 10,
+        1 => 20,
+        2 => panic!("two"),
+        _ => 30,
+    }
+}
+
+pub enum Three {
+    A,
+    B,
+    C,
+}
+
+// The otherwise branch of an exhaustive match is unreachable.
+
+#[no_mangle]
+pub fn unreachable_otherwise(x: Three) -> u32 {
+    // CHECK-LABEL: @unreachable_otherwise
+    // CHECK: switch i{{[0-9]+}} %{{.*}}, label %{{.*}} [
+    // CHECK: ], !prof ![[UNREACHABLE:[0-9]+]]
+    match x {
+        Three::A => 1,
+        Three::B => 2,
+        Three::C => 3,
+    }
+}
+
+// CHECK: ![[COLD_ARM]] = !{!"branch_weights", i32 2000, i32 2000, i32 2000, i32 1}
+// CHECK: ![[UNREACHABLE]] = !{!"branch_weights", i32 1, i32 2000, i32 2000, i32 2000}
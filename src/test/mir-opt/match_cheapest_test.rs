@@ -0,0 +1,19 @@
+// Check that a match on a string and an enum first switches on the discriminant of the enum, and
+// only calls `PartialEq::eq` on the string once the variant has matched, as the switch is cheaper.
+
+enum Kind {
+    A,
+    B,
+}
+
+// EMIT_MIR match_cheapest_test.lookup.SimplifyCfg-initial.after.mir
+fn lookup(x: (&str, Kind)) -> u8 {
+    match x {
+        ("a", Kind::A) => 0,
+        _ => 1,
+    }
+}
+
+fn main() {
+    lookup(("a", Kind::B));
+}
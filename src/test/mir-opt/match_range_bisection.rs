@@ -0,0 +1,40 @@
+// Check that a run of at least four disjoint ranges is dispatched by bisection: the first test
+// checks whether the value is below the start of the middle range, starting from the least value
+// of the type, and only then are the ranges of each half tested one after the other.
+
+#![feature(exclusive_range_pattern)]
+
+// EMIT_MIR match_range_bisection.unsigned.SimplifyCfg-initial.after.mir
+fn unsigned(x: u8) -> u8 {
+    match x {
+        0..=9 => 0,
+        20..=29 => 1,
+        40..=49 => 2,
+        60..=69 => 3,
+        _ => 4,
+    }
+}
+
+// EMIT_MIR match_range_bisection.signed.SimplifyCfg-initial.after.mir
+fn signed(x: i8) -> u8 {
+    match x {
+        -100..-50 => 0,
+        -10 => 1,
+        0..10 => 2,
+        50..=100 => 3,
+        _ => 4,
+    }
+}
+
+// EMIT_MIR match_range_bisection.char_class.SimplifyCfg-initial.after.mir
+fn char_class(c: char) -> u8 {
+    match c {
+        '0'..='9' => 0,
+        'A'..='Z' => 1,
+        '_' => 2,
+        'a'..='z' => 3,
+        _ => 4,
+    }
+}
+
+fn main() {}
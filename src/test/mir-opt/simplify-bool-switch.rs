@@ -0,0 +1,16 @@
+// A match on two booleans is merged into a single switch on both of them. The blocks switching on
+// the second boolean are left for `SimplifyCfg` to remove.
+
+// EMIT_MIR simplify_bool_switch.both.SimplifyBoolSwitch.diff
+fn both(x: (bool, bool)) -> u32 {
+    match x {
+        (false, false) => 0,
+        (false, true) => 1,
+        (true, false) => 2,
+        (true, true) => 3,
+    }
+}
+
+fn main() {
+    both((true, false));
+}
@@ -0,0 +1,14 @@
+// The two comparisons testing a range pattern are folded into a wrapping subtraction and a single
+// comparison. The block with the second comparison is left for `SimplifyCfg` to remove.
+
+// EMIT_MIR simplify_range_comparison.in_range.SimplifyRangeComparison.diff
+fn in_range(x: i32) -> u32 {
+    match x {
+        10..=20 => 1,
+        _ => 0,
+    }
+}
+
+fn main() {
+    in_range(15);
+}
@@ -0,0 +1,89 @@
+// run-pass
+// Test that `check_synthetic_match` checks patterns built outside of any body, and that it
+// returns the lints it raises instead of emitting them.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_mir_build;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_hir::RangeEnd;
+use rustc_interface::interface;
+use rustc_middle::thir::{Pat, PatKind, PatRange};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_mir_build::thir::check_synthetic_match;
+use rustc_session::config::{Input, Options};
+use rustc_session::DiagnosticOutput;
+use rustc_span::def_id::CRATE_DEF_ID;
+use rustc_span::source_map::FileName;
+use rustc_span::DUMMY_SP;
+
+const SOURCE: &str = "fn main() {}";
+
+fn range<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, lo: u128, hi: u128) -> Pat<'tcx> {
+    let param_env_and_ty = ty::ParamEnv::empty().and(ty);
+    let lo = ty::Const::from_bits(tcx, lo, param_env_and_ty);
+    let hi = ty::Const::from_bits(tcx, hi, param_env_and_ty);
+    let kind = PatKind::Range(PatRange { lo, hi, end: RangeEnd::Included });
+    Pat { ty, span: DUMMY_SP, kind: Box::new(kind) }
+}
+
+fn main() {
+    let name = FileName::anon_source_code(SOURCE);
+    let config = interface::Config {
+        opts: Options::default(),
+        crate_cfg: Default::default(),
+        input: Input::Str { name, input: SOURCE.to_string() },
+        input_path: None,
+        output_file: None,
+        output_dir: None,
+        file_loader: None,
+        diagnostic_output: DiagnosticOutput::Default,
+        stderr: None,
+        lint_caps: Default::default(),
+        parse_sess_created: None,
+        register_lints: None,
+        override_queries: None,
+        make_codegen_backend: None,
+        registry: rustc_driver::diagnostics_registry(),
+    };
+
+    interface::run_compiler(config, |compiler| {
+        compiler.enter(|queries| {
+            queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+                let ty = tcx.types.u8;
+                // Like `match x { 0..=10 => {} 10..=20 => {} 5..=5 => {} }` on a `u8`.
+                let arms = [
+                    (range(tcx, ty, 0, 10), false),
+                    (range(tcx, ty, 10, 20), false),
+                    (range(tcx, ty, 5, 5), false),
+                ];
+                let report = check_synthetic_match(
+                    tcx,
+                    ty::ParamEnv::empty(),
+                    CRATE_DEF_ID.to_def_id(),
+                    ty,
+                    &arms,
+                );
+                assert_eq!(report.arm_is_reachable, [true, true, false]);
+                let missing: Vec<_> =
+                    report.missing_patterns.iter().map(Pat::to_string).collect();
+                assert_eq!(missing, ["21_u8..=u8::MAX"]);
+                assert!(!report.is_incomplete);
+                assert_eq!(report.lints.len(), 1);
+                let message = report.lints[0].message();
+                assert_eq!(message, "multiple patterns overlap on their endpoints");
+            });
+        });
+        assert!(!compiler.session().has_errors());
+    });
+}
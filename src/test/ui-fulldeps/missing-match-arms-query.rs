@@ -0,0 +1,79 @@
+// run-pass
+// Test that the `missing_match_arms` query returns the missing arms of the non-exhaustive
+// matches of a body, and that it reports nothing, not even the lints and errors of its patterns.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_interface::interface;
+use rustc_session::config::{Input, Options};
+use rustc_session::DiagnosticOutput;
+use rustc_span::source_map::FileName;
+
+const SOURCE: &str = r#"
+#![deny(overlapping_range_endpoints)]
+
+fn main() {
+    let x = Some(true);
+    match x {
+        Some(true) => {}
+    }
+    match x {
+        Some(_) => {}
+        None => {}
+    }
+    match 0u8 {
+        0..=10 => {}
+        10..=u8::MAX => {}
+    }
+    match 0u8 {
+        5..=1 => {}
+        _ => {}
+    }
+}
+"#;
+
+fn main() {
+    let name = FileName::anon_source_code(SOURCE);
+    let config = interface::Config {
+        opts: Options::default(),
+        crate_cfg: Default::default(),
+        input: Input::Str { name, input: SOURCE.to_string() },
+        input_path: None,
+        output_file: None,
+        output_dir: None,
+        file_loader: None,
+        diagnostic_output: DiagnosticOutput::Default,
+        stderr: None,
+        lint_caps: Default::default(),
+        parse_sess_created: None,
+        register_lints: None,
+        override_queries: None,
+        make_codegen_backend: None,
+        registry: rustc_driver::diagnostics_registry(),
+    };
+
+    interface::run_compiler(config, |compiler| {
+        compiler.enter(|queries| {
+            queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+                let mut missing_arms = Vec::new();
+                for def_id in tcx.body_owners() {
+                    missing_arms.extend(tcx.missing_match_arms(def_id).values().cloned());
+                }
+                // Only the first match is missing arms, and they are listed in no set order.
+                assert_eq!(missing_arms.len(), 1);
+                missing_arms[0].sort();
+                assert_eq!(missing_arms[0], ["None", "Some(false)"]);
+            });
+        });
+        assert!(!compiler.session().has_errors());
+    });
+}
@@ -0,0 +1,8 @@
+// edition:2018
+
+async fn f((0, x): (u8, u8)) -> u8 {
+    //~^ ERROR refutable pattern in function argument
+    x
+}
+
+fn main() {}
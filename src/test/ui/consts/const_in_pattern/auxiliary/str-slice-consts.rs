@@ -0,0 +1,3 @@
+pub const GREETING: &str = "hello";
+pub const MAGIC: &[u8] = b"\x7fELF";
+pub const EMPTY: &[u8] = &[];
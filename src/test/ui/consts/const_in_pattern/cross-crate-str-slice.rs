@@ -0,0 +1,33 @@
+// run-pass
+// aux-build:str-slice-consts.rs
+
+// Check that `&str` and `&[u8]` constants from another crate can be used as patterns.
+
+extern crate str_slice_consts;
+use str_slice_consts::{EMPTY, GREETING, MAGIC};
+
+fn greeting(s: &str) -> bool {
+    match s {
+        GREETING => true,
+        _ => false,
+    }
+}
+
+fn kind(bytes: &[u8]) -> u8 {
+    match bytes {
+        EMPTY => 0,
+        MAGIC => 1,
+        [0x7f, ..] => 2,
+        [_, ..] => 3,
+    }
+}
+
+fn main() {
+    assert!(greeting("hello"));
+    assert!(!greeting("hell"));
+
+    assert_eq!(kind(b""), 0);
+    assert_eq!(kind(b"\x7fELF"), 1);
+    assert_eq!(kind(b"\x7fELF\x02"), 2);
+    assert_eq!(kind(b"MZ"), 3);
+}
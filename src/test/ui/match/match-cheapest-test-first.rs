@@ -0,0 +1,40 @@
+// run-pass
+// compile-flags: -Z validate-match-lowering
+
+// When a candidate needs several tests, the cheapest ones are done first: here the variant of
+// the second field is switched on before the string in the first one is compared. Check that the
+// arms are still chosen in order.
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Keyword,
+    Ident,
+    Number(u32),
+}
+
+fn classify(token: (&str, Kind, &[u8])) -> u8 {
+    match token {
+        ("fn", Kind::Keyword, _) => 0,
+        ("let", Kind::Keyword, []) => 1,
+        (_, Kind::Keyword, _) => 2,
+        ("x", Kind::Ident, [b'x']) => 3,
+        (_, Kind::Number(0..=9), [_]) => 4,
+        (_, Kind::Number(n), _) if n % 2 == 0 => 5,
+        ("1", _, _) => 6,
+        _ => 7,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(("fn", Kind::Keyword, b"")), 0);
+    assert_eq!(classify(("let", Kind::Keyword, b"")), 1);
+    assert_eq!(classify(("let", Kind::Keyword, b"let")), 2);
+    assert_eq!(classify(("fn", Kind::Ident, b"fn")), 7);
+    assert_eq!(classify(("x", Kind::Ident, b"x")), 3);
+    assert_eq!(classify(("x", Kind::Ident, b"xy")), 7);
+    assert_eq!(classify(("3", Kind::Number(3), b"3")), 4);
+    assert_eq!(classify(("12", Kind::Number(12), b"12")), 5);
+    assert_eq!(classify(("1", Kind::Number(13), b"13")), 6);
+    assert_eq!(classify(("1", Kind::Number(1), b"1")), 4);
+    assert_eq!(classify(("13", Kind::Number(13), b"13")), 7);
+}
@@ -0,0 +1,18 @@
+// check-pass
+// compile-flags: -Z unpretty=match-decision-tree
+
+// Check the decision tree printed for a match that switches on a discriminant, then on an
+// integer, and then tests a range.
+
+fn classify(x: Option<u8>) -> u8 {
+    match x {
+        Some(0) => 1,
+        Some(1..=9) => 2,
+        Some(_) => 3,
+        None => 4,
+    }
+}
+
+fn main() {
+    classify(Some(5));
+}
@@ -0,0 +1,113 @@
+// run-pass
+// compile-flags: -Z validate-match-lowering
+
+// Matches on many disjoint ranges are lowered to a balanced tree of comparisons. Check that every
+// value still reaches the arm it would reach when testing the arms one after the other.
+
+#![feature(exclusive_range_pattern)]
+
+fn category(c: char) -> u8 {
+    match c {
+        '0'..='9' => 0,
+        'A'..='Z' => 1,
+        '_' => 2,
+        'a'..='z' => 3,
+        '\u{c0}'..='\u{d6}' => 4,
+        '\u{d8}'..'\u{f7}' => 5,
+        '\u{370}'..='\u{3ff}' => 6,
+        _ => 7,
+    }
+}
+
+fn category_linear(c: char) -> u8 {
+    let c = c as u32;
+    if (48..=57).contains(&c) {
+        0
+    } else if (65..=90).contains(&c) {
+        1
+    } else if c == 95 {
+        2
+    } else if (97..=122).contains(&c) {
+        3
+    } else if (0xc0..=0xd6).contains(&c) {
+        4
+    } else if (0xd8..0xf7).contains(&c) {
+        5
+    } else if (0x370..=0x3ff).contains(&c) {
+        6
+    } else {
+        7
+    }
+}
+
+fn signed(x: i8, b: bool) -> u8 {
+    match x {
+        i8::MIN..=-100 => 0,
+        -99..-50 if b => 1,
+        -50 => 2,
+        -10..=-1 => 3,
+        1..=9 => 4,
+        10..100 => 5,
+        100..=i8::MAX => 6,
+        _ => 7,
+    }
+}
+
+fn signed_linear(x: i8, b: bool) -> u8 {
+    if x <= -100 {
+        0
+    } else if -99 <= x && x < -50 && b {
+        1
+    } else if x == -50 {
+        2
+    } else if -10 <= x && x <= -1 {
+        3
+    } else if 1 <= x && x <= 9 {
+        4
+    } else if 10 <= x && x < 100 {
+        5
+    } else if x >= 100 {
+        6
+    } else {
+        7
+    }
+}
+
+// Overlapping ranges are tested in order.
+fn overlapping(x: u16) -> u8 {
+    match x {
+        0..=10 => 0,
+        5..=20 => 1,
+        15..=30 => 2,
+        25..=40 => 3,
+        _ => 4,
+    }
+}
+
+fn overlapping_linear(x: u16) -> u8 {
+    if x <= 10 {
+        0
+    } else if x <= 20 {
+        1
+    } else if x <= 30 {
+        2
+    } else if x <= 40 {
+        3
+    } else {
+        4
+    }
+}
+
+fn main() {
+    for c in (0..0x500).filter_map(std::char::from_u32) {
+        assert_eq!(category(c), category_linear(c), "{:?}", c);
+    }
+    for x in i8::MIN..=i8::MAX {
+        for b in [false, true] {
+            assert_eq!(signed(x, b), signed_linear(x, b), "{} {}", x, b);
+        }
+    }
+    for x in 0..100 {
+        assert_eq!(overlapping(x), overlapping_linear(x), "{}", x);
+    }
+}
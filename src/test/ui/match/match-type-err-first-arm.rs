@@ -24,7 +24,7 @@ fn test_func2(n: i32) -> i32 {
 
 fn test_func3(n: i32) -> i32 {
     let x = match n { //~ NOTE `match` arms have incompatible types
-        1 => 'b',
+        1 => 'b', //~ NOTE the first arm is found to be of type `char`
         2 => 'b',
         3 => 'b',
         4 => 'b',
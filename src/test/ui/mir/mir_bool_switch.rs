@@ -0,0 +1,35 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=3
+
+// A match on two booleans is merged into a single switch on both of them. Check that every
+// combination still reaches its arm.
+
+fn both(a: bool, b: bool) -> u8 {
+    match (a, b) {
+        (false, false) => 0,
+        (false, true) => 1,
+        (true, false) => 2,
+        (true, true) => 3,
+    }
+}
+
+fn through_ref(pair: &(bool, bool)) -> u8 {
+    match *pair {
+        (true, true) => 10,
+        (false, true) => 20,
+        (true, false) => 30,
+        (false, false) => 40,
+    }
+}
+
+fn main() {
+    assert_eq!(both(false, false), 0);
+    assert_eq!(both(false, true), 1);
+    assert_eq!(both(true, false), 2);
+    assert_eq!(both(true, true), 3);
+
+    assert_eq!(through_ref(&(true, true)), 10);
+    assert_eq!(through_ref(&(false, true)), 20);
+    assert_eq!(through_ref(&(true, false)), 30);
+    assert_eq!(through_ref(&(false, false)), 40);
+}
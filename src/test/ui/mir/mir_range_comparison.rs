@@ -0,0 +1,60 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=3
+
+// The two comparisons testing a range pattern are folded into a wrapping subtraction and a single
+// comparison. Check the values at and just past both ends of each range.
+
+#![feature(exclusive_range_pattern)]
+
+fn signed(x: i8) -> bool {
+    matches!(x, -10..=20)
+}
+
+fn unsigned(x: u16) -> bool {
+    matches!(x, 100..=200)
+}
+
+fn exclusive(x: i64) -> bool {
+    matches!(x, -5..5)
+}
+
+fn full(x: i8) -> bool {
+    matches!(x, i8::MIN..=i8::MAX)
+}
+
+fn letter(c: char) -> bool {
+    matches!(c, 'a'..='z')
+}
+
+fn main() {
+    assert!(!signed(i8::MIN));
+    assert!(!signed(-11));
+    assert!(signed(-10));
+    assert!(signed(0));
+    assert!(signed(20));
+    assert!(!signed(21));
+    assert!(!signed(i8::MAX));
+
+    assert!(!unsigned(0));
+    assert!(!unsigned(99));
+    assert!(unsigned(100));
+    assert!(unsigned(200));
+    assert!(!unsigned(201));
+    assert!(!unsigned(u16::MAX));
+
+    assert!(!exclusive(i64::MIN));
+    assert!(!exclusive(-6));
+    assert!(exclusive(-5));
+    assert!(exclusive(4));
+    assert!(!exclusive(5));
+    assert!(!exclusive(i64::MAX));
+
+    assert!(full(i8::MIN));
+    assert!(full(i8::MAX));
+
+    assert!(!letter('`'));
+    assert!(letter('a'));
+    assert!(letter('z'));
+    assert!(!letter('{'));
+    assert!(!letter('\u{10ffff}'));
+}
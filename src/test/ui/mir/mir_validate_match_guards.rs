@@ -0,0 +1,23 @@
+// run-pass
+// compile-flags: -Z validate-mir
+
+// Matches with guards are checked by the MIR validator: the fake borrows taken for each guard
+// must be in place on every path into it, and the decision tree must agree with match checking.
+
+fn classify(pair: &(Option<u8>, u8)) -> u8 {
+    match *pair {
+        (Some(x), y) if x > y => 0,
+        (Some(x), ref y) if x + 1 == *y => 1,
+        (None, 0) => 2,
+        (None, y) if y % 2 == 0 => 3,
+        _ => 4,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(&(Some(5), 1)), 0);
+    assert_eq!(classify(&(Some(1), 2)), 1);
+    assert_eq!(classify(&(None, 0)), 2);
+    assert_eq!(classify(&(None, 4)), 3);
+    assert_eq!(classify(&(None, 3)), 4);
+}
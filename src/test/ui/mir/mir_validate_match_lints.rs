@@ -0,0 +1,12 @@
+// check-pass
+// compile-flags: -Z validate-mir
+// Test that validating the decision trees of matches doesn't report their lints a second time.
+
+fn main() {
+    match 5u8 {
+        0..=10 => {}
+        10..=20 => {} //~ WARN multiple patterns overlap on their endpoints
+        5 => {} //~ WARN unreachable pattern
+        _ => {}
+    }
+}
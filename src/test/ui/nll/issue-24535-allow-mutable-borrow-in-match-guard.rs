@@ -9,6 +9,7 @@ fn main() {
     rust_issue_24535();
     rfcs_issue_1006_1();
     rfcs_issue_1006_2();
+    disjoint_mut_borrow_in_guard();
 }
 
 fn rust_issue_24535() {
@@ -53,3 +54,29 @@ fn check<'a, I: Iterator<Item=&'a i32>>(mut i: I) -> bool {
         _ => { panic!("nope"); }
     };
 }
+
+fn disjoint_mut_borrow_in_guard() {
+    struct Checker {
+        kind: Option<u8>,
+    }
+
+    impl Checker {
+        fn check(&self, scratch: &mut Vec<u8>) -> bool {
+            scratch.push(self.kind.unwrap_or(0));
+            scratch.len() > 1
+        }
+
+        fn run(&self) -> usize {
+            let mut scratch = vec![];
+            loop {
+                match self.kind {
+                    Some(_) if self.check(&mut scratch) => return scratch.len(),
+                    Some(_) => {}
+                    None => panic!("nope"),
+                }
+            }
+        }
+    }
+
+    assert_eq!(Checker { kind: Some(7) }.run(), 2);
+}
@@ -0,0 +1,16 @@
+// run-rustfix
+
+// `let` written where a pattern starts is reported with a fix, and the rest is parsed as the
+// pattern.
+
+fn main() {
+    let x = Some(1);
+    match x {
+        let Some(y) => assert_eq!(y, 1), //~ ERROR expected pattern, found `let`
+        None => {}
+    }
+    if let let Some(mut y) = x { //~ ERROR expected pattern, found `let`
+        y += 1;
+        assert_eq!(y, 2);
+    }
+}
@@ -0,0 +1,18 @@
+// run-rustfix
+
+// A missing comma after an arm whose body is not a block is reported once per arm, and the arms
+// after it are still parsed.
+
+fn describe(x: Option<u8>) -> &'static str {
+    match x {
+        None => "none"
+        Some(0) => "zero" //~ ERROR expected `,` following `match` arm
+        Some(n) if n % 2 == 0 => "even", //~ ERROR expected `,` following `match` arm
+        Some(_) => "odd",
+    }
+}
+
+fn main() {
+    assert_eq!(describe(None), "none");
+    assert_eq!(describe(Some(3)), "odd");
+}
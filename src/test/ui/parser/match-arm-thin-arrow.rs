@@ -0,0 +1,11 @@
+// run-rustfix
+
+fn main() {
+    let x = 2;
+    let _ = match x {
+        0 -> "zero", //~ ERROR expected `=>`, found `->`
+        1 => "one",
+        n if n % 2 == 0 -> "even", //~ ERROR expected `=>`, found `->`
+        _ => "odd",
+    };
+}
@@ -46,7 +46,7 @@ fn main() {
     }
     match S::get(16) {
         Some(Val::Foo) => 17
-        _ => 18, //~ ERROR expected one of
+        _ => 18, //~ ERROR expected `,` following `match` arm
     }
     match S::get(19) {
         Some(Val::Foo) =>
@@ -0,0 +1,4 @@
+pub enum E {
+    A(u8),
+    B(u8, u8),
+}
@@ -0,0 +1,12 @@
+// aux-build:bare-tuple-variant-path.rs
+
+extern crate bare_tuple_variant_path;
+
+use bare_tuple_variant_path::E;
+
+fn main() {
+    match E::A(0) {
+        E::A => {} //~ ERROR expected unit struct, unit variant or constant, found tuple variant `E::A`
+        E::B => {} //~ ERROR expected unit struct, unit variant or constant, found tuple variant `E::B`
+    }
+}
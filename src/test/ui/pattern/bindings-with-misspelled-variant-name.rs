@@ -0,0 +1,27 @@
+// Check that a capitalized binding whose name is close to a unit variant of the matched `enum`
+// is reported as a likely misspelling of that variant.
+
+// check-pass
+// edition:2018
+
+#![allow(unused_variables)]
+#![allow(non_snake_case)]
+#![allow(unreachable_patterns)]
+
+mod colors {
+    pub enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+}
+
+fn name(c: colors::Color) -> &'static str {
+    match c {
+        Gren => "green",
+        //~^ WARN pattern binding `Gren` is named similarly to the variant
+        Other => "other",
+    }
+}
+
+fn main() {}
@@ -0,0 +1,42 @@
+// Check that the suggestion to qualify a binding named like a variant uses the name that is in
+// scope at the binding, or the full path if there is none, and that a binding with a sub-pattern
+// is only offered a rename.
+
+// check-pass
+// edition:2018
+
+#![allow(unused_variables)]
+#![allow(non_snake_case)]
+
+mod m {
+    pub enum Kind {
+        Unit,
+    }
+}
+
+use m::Kind as Renamed;
+
+fn renamed(k: Renamed) {
+    match k {
+        Unit => {}
+        //~^ WARN pattern binding `Unit` is named the same as one of the variants of the type `Kind`
+    }
+}
+
+fn with_subpattern(k: Renamed) {
+    match k {
+        Unit @ _ => {}
+        //~^ WARN pattern binding `Unit` is named the same as one of the variants of the type `Kind`
+    }
+}
+
+mod n {
+    pub fn not_imported(k: crate::m::Kind) {
+        match k {
+            Unit => {}
+            //~^ WARN pattern binding `Unit` is named the same as one of the variants of the type
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// run-rustfix
+
+// When a tuple struct pattern with too few fields already ignores all of them, ignoring them
+// with `..` is the fix to apply.
+
+struct S(u8, u8, u8);
+
+fn main() {
+    match S(1, 2, 3) {
+        S(_) => {}
+        //~^ ERROR this pattern has 1 field, but the corresponding tuple struct has 3 fields
+    }
+    match S(1, 2, 3) {
+        S() => {}
+        //~^ ERROR this pattern has 0 fields, but the corresponding tuple struct has 3 fields
+    }
+}
@@ -0,0 +1,36 @@
+// The matches of derived impls are only checked for exhaustiveness, which is still an error.
+// Impls that are only marked `#[automatically_derived]` by hand are checked in full.
+
+#![deny(unreachable_patterns)]
+
+#[derive(Clone, PartialEq)]
+enum E {
+    A(bool),
+    B { x: u8 },
+    C,
+}
+
+struct S(bool);
+
+#[automatically_derived]
+impl Clone for S {
+    fn clone(&self) -> S {
+        match self.0 {
+            //~^ ERROR non-exhaustive patterns: `false` not covered
+            true => S(true),
+        }
+    }
+}
+
+#[automatically_derived]
+impl Default for S {
+    fn default() -> S {
+        match true {
+            _ => {}
+            true => {} //~ ERROR unreachable pattern
+        }
+        S(false)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#![crate_type = "rlib"]
+
+#[macro_export]
+macro_rules! match_or_default {
+    ($e:expr, $($p:pat)|*) => {
+        match $e {
+            $($p => {})*
+            _ => {}
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! if_let_some {
+    ($e:expr) => {
+        if let x = $e { Some(x) } else { None }
+    };
+}
@@ -0,0 +1,16 @@
+// A denied lint from match checking is not an error in the body: it is still lowered to MIR and
+// borrow-checked.
+
+#![deny(unreachable_patterns)]
+
+fn main() {
+    let x = 0u8;
+    match x {
+        _ => {}
+        0 => {} //~ ERROR unreachable pattern
+    }
+
+    let s = String::new();
+    drop(s);
+    drop(s); //~ ERROR use of moved value: `s`
+}
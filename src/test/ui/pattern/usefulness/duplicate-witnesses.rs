@@ -0,0 +1,12 @@
+// Witnesses found along different paths of the search can end up as the same pattern. Each one is
+// only listed once.
+
+fn main() {
+    let x: (Option<bool>, Option<bool>) = (None, None);
+    match x {
+        //~^ ERROR non-exhaustive patterns: `(_, None)` not covered
+        (Some(true), Some(_)) => {}
+        (Some(false), Some(_)) => {}
+        (None, Some(_)) => {}
+    }
+}
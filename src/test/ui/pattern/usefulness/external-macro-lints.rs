@@ -0,0 +1,24 @@
+// Pattern lints about code written in a macro from another crate are not reported, the user
+// cannot fix them. Those about code written in a local macro still are.
+
+// aux-build:pattern-macros.rs
+
+#![deny(unreachable_patterns, irrefutable_let_patterns)]
+
+#[macro_use]
+extern crate pattern_macros;
+
+macro_rules! local_match_or_default {
+    ($e:expr, $($p:pat)|*) => {
+        match $e {
+            $($p => {})*
+            _ => {} //~ ERROR unreachable pattern
+        }
+    };
+}
+
+fn main() {
+    match_or_default!(0u8, 0..=255);
+    let _ = if_let_some!(0u8);
+    local_match_or_default!(0u8, 0..=255);
+}
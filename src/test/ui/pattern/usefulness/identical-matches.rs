@@ -0,0 +1,19 @@
+// Identical matches are only checked once, but each of them gets its own diagnostics.
+
+#![deny(unreachable_patterns)]
+
+macro_rules! m {
+    ($a:pat, $b:pat) => {
+        match 0u8 {
+            $a => {}
+            $b => {}
+            _ => {}
+        }
+    };
+}
+
+fn main() {
+    m!(1, 1); //~ ERROR unreachable pattern
+    m!(2, 2); //~ ERROR unreachable pattern
+    m!(1, 1); //~ ERROR unreachable pattern
+}
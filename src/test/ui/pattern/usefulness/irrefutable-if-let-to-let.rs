@@ -0,0 +1,15 @@
+// run-rustfix
+
+#![deny(irrefutable_let_patterns)]
+
+fn main() {
+    if let x = 5 { //~ ERROR irrefutable `if let` pattern
+        println!("{}", x);
+    }
+
+    let () = {
+        if let (a, b) = (1, 2) { //~ ERROR irrefutable `if let` pattern
+            println!("{} {}", a, b);
+        }
+    };
+}
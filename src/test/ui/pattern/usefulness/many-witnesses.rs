@@ -0,0 +1,30 @@
+// Only some of the missing patterns of a match with a great many of them are kept, so the count of
+// the ones that aren't listed is a lower bound.
+
+enum E { V0, V1, V2, V3, V4, V5, V6, V7, V8, V9, V10, V11, V12, V13, V14, V15, V16 }
+
+fn f(pair: (E, E)) {
+    use E::*;
+    match pair {
+        //~^ ERROR non-exhaustive patterns: `(V0, V1)`, `(V0, V2)`, `(V0, V3)` and at least 253
+        (V0, V0) => {}
+        (V1, V1) => {}
+        (V2, V2) => {}
+        (V3, V3) => {}
+        (V4, V4) => {}
+        (V5, V5) => {}
+        (V6, V6) => {}
+        (V7, V7) => {}
+        (V8, V8) => {}
+        (V9, V9) => {}
+        (V10, V10) => {}
+        (V11, V11) => {}
+        (V12, V12) => {}
+        (V13, V13) => {}
+        (V14, V14) => {}
+        (V15, V15) => {}
+        (V16, V16) => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+// compile-flags: -Z match-check-budget=2
+// Listing the missing patterns gets a budget of its own, so a match that was checked in full
+// within the budget still gets them reported.
+
+fn main() {
+    match true {
+        //~^ ERROR non-exhaustive patterns: `false` not covered
+        true => {}
+    }
+}
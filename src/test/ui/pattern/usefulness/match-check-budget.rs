@@ -0,0 +1,21 @@
+// compile-flags: -Z match-check-budget=1
+// Past the budget, every arm is assumed reachable and only a `_` arm makes a match exhaustive.
+
+fn pair() -> (bool, bool) {
+    (true, false)
+}
+
+fn main() {
+    match pair() {
+        //~^ WARN this `match` is too complex to fully analyze
+        //~| ERROR non-exhaustive patterns: `_` not covered
+        (true, _) => {}
+        (false, _) => {}
+    }
+    match pair() {
+        //~^ WARN this `match` is too complex to fully analyze
+        (true, _) => {}
+        (true, _) => {}
+        _ => {}
+    }
+}
@@ -0,0 +1,18 @@
+// Witnesses that together cover a whole nested sub-space are reported as a single witness with a
+// wildcard there, at the shallowest position that is still not covered.
+
+fn main() {
+    let x: (Option<bool>, bool) = (None, true);
+    match x {
+        //~^ ERROR non-exhaustive patterns: `(Some(_), false)` not covered
+        (Some(true), true) => {}
+        (Some(false), true) => {}
+        (None, _) => {}
+    }
+    match x {
+        //~^ ERROR non-exhaustive patterns: `(_, false)` not covered
+        (Some(true), true) => {}
+        (Some(false), true) => {}
+        (None, true) => {}
+    }
+}
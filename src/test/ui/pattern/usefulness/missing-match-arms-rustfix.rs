@@ -0,0 +1,34 @@
+// run-rustfix
+// The missing arms of a non-exhaustive match are suggested for tools like rustfix to apply. The
+// new arms are `todo!()` placeholders, so the suggestion isn't machine-applicable.
+
+#![allow(dead_code)]
+
+enum Direction {
+    North,
+    East,
+    South,
+}
+
+fn direction(d: Direction) -> u8 {
+    match d {
+        //~^ ERROR non-exhaustive patterns: `East` and `South` not covered
+        Direction::North => 0,
+    }
+}
+
+fn nested(x: Option<Option<bool>>) -> u8 {
+    match x {
+        //~^ ERROR non-exhaustive patterns: `Some(Some(false))` not covered
+        None => 0,
+        Some(None) => 1,
+        Some(Some(true)) => 2,
+    }
+}
+
+fn empty(x: u8) -> u8 {
+    match x {}
+    //~^ ERROR non-exhaustive patterns: type `u8` is non-empty
+}
+
+fn main() {}
@@ -45,12 +45,12 @@ fn by_ref_once(e: &E) {
 }
 
 fn by_ref_thrice(e: & &mut &E) {
-    match e { //~ ERROR non-exhaustive patterns: `&&mut &B` and `&&mut &C` not covered
+    match e { //~ ERROR non-exhaustive patterns: `B` and `C` not covered
         E::A => {}
     }
 
     let E::A = e;
-    //~^ ERROR refutable pattern in local binding: `&&mut &B` and `&&mut &C` not covered
+    //~^ ERROR refutable pattern in local binding: `B` and `C` not covered
 }
 
 enum Opt {
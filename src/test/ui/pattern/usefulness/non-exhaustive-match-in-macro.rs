@@ -0,0 +1,27 @@
+// Non-exhaustive `match`es written inside a macro are reported at the macro's invocation, once per
+// invocation.
+
+enum E { A, B }
+
+macro_rules! match_a {
+    () => {
+        match E::A {
+            E::A => {}
+        }
+    };
+}
+
+macro_rules! match_a_for_each {
+    ($($x:expr),*) => {
+        $(
+            match E::A {
+                E::A => { let _ = $x; }
+            }
+        )*
+    };
+}
+
+fn main() {
+    match_a!(); //~ ERROR non-exhaustive patterns: `B` not covered
+    match_a_for_each!(1, 2, 3); //~ ERROR non-exhaustive patterns: `B` not covered
+}
@@ -0,0 +1,12 @@
+// compile-flags: --error-format=json
+
+// The missing arms of a non-exhaustive match are handed to tools in the JSON output.
+
+enum E { A, B }
+
+fn main() {
+    match E::A {
+        E::A => {}
+    }
+    //~^^^ ERROR non-exhaustive patterns: `B` not covered
+}
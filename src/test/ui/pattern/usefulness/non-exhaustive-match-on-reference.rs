@@ -0,0 +1,29 @@
+// Check the suggestions offered when every missing pattern is behind a reference.
+
+enum E {
+    A,
+    B,
+}
+
+fn by_ref(e: &E) {
+    match e {
+        //~^ ERROR non-exhaustive patterns: `&B` not covered
+        E::A => {}
+    }
+}
+
+fn explicit_ref(e: &E) {
+    match e {
+        //~^ ERROR non-exhaustive patterns: `&B` not covered
+        &E::A => {}
+    }
+}
+
+fn borrowed(e: E) {
+    match &e {
+        //~^ ERROR non-exhaustive patterns: `&B` not covered
+        E::A => {}
+    }
+}
+
+fn main() {}
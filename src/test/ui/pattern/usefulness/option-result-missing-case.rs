@@ -0,0 +1,25 @@
+// Matches on `Option` and `Result` that miss one of their variants name the missing case.
+
+fn option(x: Option<u8>) {
+    match x {
+        //~^ ERROR non-exhaustive patterns: `None` not covered
+        Some(_) => {}
+    }
+}
+
+fn result(x: Result<u8, ()>) {
+    match x {
+        //~^ ERROR non-exhaustive patterns: `Ok(_)` not covered
+        Err(()) => {}
+    }
+}
+
+fn partial(x: Option<bool>) {
+    match x {
+        //~^ ERROR non-exhaustive patterns: `Some(false)` not covered
+        None => {}
+        Some(true) => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+#![deny(repeated_guarded_arms)]
+
+fn next() -> bool {
+    true
+}
+
+fn main() {
+    let limit = 10;
+    match Some(5u8) {
+        Some(x) if x > limit => {}
+        Some(y) if y > limit => {}
+        Some(x) if x > limit => {} //~ ERROR this arm repeats the pattern and guard of an earlier arm
+        _ => {}
+    }
+    // A guard with side effects can give a different answer the second time.
+    match Some(5u8) {
+        Some(_) if next() => {}
+        Some(_) if next() => {}
+        _ => {}
+    }
+    match 5u8 {
+        n if n % 2 == 0 => 1,
+        n if n % 2 == 0 => 2, //~ ERROR this arm repeats the pattern and guard of an earlier arm
+        _ => 3,
+    };
+    // The same names are bound to different fields.
+    match (1u8, 2u8) {
+        (x, y) if x > y => {}
+        (y, x) if x > y => {}
+        _ => {}
+    }
+    // Overloaded operators and derefs run user code, which can give a different answer.
+    match Meters(5) {
+        m if m > LIMIT => {}
+        m if m > LIMIT => {}
+        _ => {}
+    }
+    match Wrapper { inner: Meters(5) } {
+        w if w.0 > 3 => {}
+        w if w.0 > 3 => {}
+        _ => {}
+    }
+}
+
+#[derive(PartialEq, PartialOrd)]
+struct Meters(u8);
+
+const LIMIT: Meters = Meters(3);
+
+struct Wrapper {
+    inner: Meters,
+}
+
+impl std::ops::Deref for Wrapper {
+    type Target = Meters;
+
+    fn deref(&self) -> &Meters {
+        &self.inner
+    }
+}
@@ -0,0 +1,12 @@
+#![deny(unreachable_patterns)]
+
+fn main() {
+    match Some(true) {
+        Some(true | false) | None => {}
+        Some(_) => {} //~ ERROR unreachable pattern
+    }
+    match (0u8, 'a') {
+        (0..=u8::MAX, _c) => {}
+        _ => {} //~ ERROR unreachable pattern
+    }
+}
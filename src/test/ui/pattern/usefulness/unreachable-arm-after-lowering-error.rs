@@ -0,0 +1,28 @@
+// An arm whose pattern fails to lower must not prevent checking the other arms.
+
+#![deny(unreachable_patterns)]
+
+static S: u8 = 0;
+
+enum E {
+    A(u8),
+    B,
+}
+
+fn main() {
+    match 0u8 {
+        0..=S => {} //~ ERROR statics cannot be referenced in patterns
+        _ => {}
+        1 => {} //~ ERROR unreachable pattern
+    }
+    // The failed arm could have covered the missing values, so none are reported.
+    match 0u8 {
+        0 => {}
+        0..=S => {} //~ ERROR statics cannot be referenced in patterns
+    }
+    // Only the subpattern that failed to lower is unknown, `B` is missing regardless.
+    match E::B {
+        //~^ ERROR non-exhaustive patterns: `B` not covered
+        E::A(0..=S) => {} //~ ERROR statics cannot be referenced in patterns
+    }
+}
@@ -0,0 +1,11 @@
+// check-pass
+// compile-flags: -Z validate-match-lowering
+// Test that checking a match again to validate its lowering doesn't report its lints twice.
+
+fn main() {
+    match 5u8 {
+        0..=10 => {}
+        10..=20 => {} //~ WARN multiple patterns overlap on their endpoints
+        _ => {}
+    }
+}
@@ -0,0 +1,43 @@
+// check-pass
+// compile-flags: -Z validate-match-lowering
+
+#![allow(unreachable_patterns)]
+
+enum E {
+    A(bool),
+    B { x: u8 },
+    C,
+}
+
+fn f(e: E, n: u32, s: &str, pair: (Option<u8>, bool)) -> u32 {
+    let a = match e {
+        E::A(true) | E::C => 0,
+        E::A(b) if b => 1,
+        E::A(_) => 2,
+        E::B { x: 0..=9 } => 3,
+        E::B { x: 10 | 20 } => 4,
+        E::B { .. } => 5,
+        E::C => 6,
+    };
+    let b = match n {
+        0 => 0,
+        1..=9 => 1,
+        5 => 2,
+        _ => 3,
+    };
+    let c = match s {
+        "a" | "b" => 0,
+        _ => 1,
+    };
+    let d = match pair {
+        (Some(0), true) => 0,
+        (None, _) | (_, false) => 1,
+        (Some(_), true) if n > 3 => 2,
+        (Some(_), _) => 3,
+    };
+    a + b + c + d
+}
+
+fn main() {
+    f(E::C, 0, "", (None, true));
+}
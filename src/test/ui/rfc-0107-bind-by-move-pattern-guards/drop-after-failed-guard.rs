@@ -0,0 +1,67 @@
+// run-pass
+
+// A by-move binding is only moved out of the scrutinee once its guard succeeds. Check that a
+// value whose guard fails is dropped exactly once, whether a later arm moves it, binds it by
+// reference, or leaves it in the scrutinee.
+
+use std::cell::Cell;
+
+struct Noisy<'a> {
+    value: u8,
+    drops: &'a Cell<u32>,
+}
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+fn moved_by_later_arm(value: u8, drops: &Cell<u32>) -> u8 {
+    let pair = (Noisy { value, drops }, 0u8);
+    match pair {
+        (n, _) if n.value == 0 => n.value,
+        (n, _) if n.value == 1 => n.value + 10,
+        (n, _) => {
+            let value = n.value + 20;
+            drop(n);
+            value
+        }
+    }
+}
+
+fn borrowed_by_later_arm(value: u8, drops: &Cell<u32>) -> u8 {
+    let opt = Some(Noisy { value, drops });
+    match opt {
+        Some(n) if n.value == 0 => n.value,
+        Some(ref n) => n.value + 10,
+        None => 255,
+    }
+}
+
+fn left_in_place(value: u8, drops: &Cell<u32>) -> u8 {
+    let opt = Some(Noisy { value, drops });
+    match opt {
+        Some(n) if n.value == 0 => n.value,
+        Some(_) => 10,
+        None => 255,
+    }
+}
+
+fn main() {
+    for value in 0..3 {
+        let drops = Cell::new(0);
+        assert_eq!(moved_by_later_arm(value, &drops), value + 10 * value);
+        assert_eq!(drops.get(), 1);
+    }
+    for (value, expected) in [(0, 0), (1, 11)] {
+        let drops = Cell::new(0);
+        assert_eq!(borrowed_by_later_arm(value, &drops), expected);
+        assert_eq!(drops.get(), 1);
+    }
+    for (value, expected) in [(0, 0), (1, 10)] {
+        let drops = Cell::new(0);
+        assert_eq!(left_in_place(value, &drops), expected);
+        assert_eq!(drops.get(), 1);
+    }
+}